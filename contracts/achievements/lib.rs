@@ -0,0 +1,1056 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::indexing_slicing
+    )
+)]
+
+/// VR Genesis Frame - Achievement Badges
+/// Soulbound badges minted by issuers the admin registers, such as game
+/// servers and event hosts. Each badge references an achievement schema its
+/// issuer published and carries the hash of the issuer's attestation, so the
+/// VR client can render trophies from chain data alone. Badges never change
+/// hands; holders may burn theirs and issuers may revoke the ones they issued,
+/// giving a reason code that stays on chain after the badge is gone. Badges
+/// may expire, for subscriptions and seasonal passes; `is_valid` is what
+/// gates should check.
+/// Also runs creator verification: applicants stake a deposit with a profile
+/// hash, and curators approve them into a verification badge the contract
+/// itself issues, or reject them and keep the deposit.
+/// Issuers can run proof-of-attendance drops: anyone who submits the event's
+/// secret during its window claims the badge, up to a supply cap. The secret
+/// is public once the first claim lands, so windows should be short.
+/// Implements `PSP34Core`, so parcels can be gated on holding badges.
+#[ink::contract]
+pub mod achievements {
+    use ink::env::hash::{Blake2x256, HashOutput};
+    use ink::prelude::{string::String, vec::Vec};
+    use ink::storage::{traits::StorageKey, Mapping};
+    use polka_space_nft_core::{index::OwnerIndex, Enumerable, PSP34Core};
+    use scale::{Decode, Encode};
+
+    /// Maximum length of a schema name in bytes
+    pub const MAX_NAME_LEN: usize = 64;
+
+    /// Maximum length of a schema URI in bytes
+    pub const MAX_URI_LEN: usize = 256;
+
+    /// Maximum number of badges an account can hold, so `badges_of` stays bounded
+    pub const MAX_BADGES_PER_ACCOUNT: u32 = 256;
+
+    /// An achievement badges can be issued for
+    #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Schema {
+        /// Account that published the schema and may issue its badges
+        pub issuer: AccountId,
+        pub name: String,
+        /// Criteria and artwork, off-chain
+        pub uri: String,
+    }
+
+    /// A badge awarded to an account
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Badge {
+        pub schema_id: u32,
+        pub owner: AccountId,
+        /// Hash of the issuer's evidence that the achievement was earned
+        pub attestation: Hash,
+        /// Block the badge was issued in
+        pub issued_at: BlockNumber,
+        /// First block the badge is no longer valid in, if it expires
+        pub expires_at: Option<BlockNumber>,
+    }
+
+    /// Record of an issuer taking back a badge
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Revocation {
+        /// Issuer-defined reason, e.g. misconduct or an expired credential
+        pub reason_code: u8,
+        /// Block the badge was revoked in
+        pub revoked_at: BlockNumber,
+    }
+
+    /// Settings of the creator verification flow
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct CreatorVerification {
+        /// Schema of the verification badge, issued by the contract itself
+        pub schema_id: u32,
+        /// Deposit an application must stake
+        pub deposit: Balance,
+    }
+
+    /// A pending creator application
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Application {
+        /// Hash of the applicant's off-chain creator profile
+        pub profile: Hash,
+        /// Deposit staked, returned on approval
+        pub deposit: Balance,
+        /// Block the application was submitted in
+        pub applied_at: BlockNumber,
+    }
+
+    /// A proof-of-attendance drop of a schema's badge
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct EventDrop {
+        pub schema_id: u32,
+        /// First block claims are accepted in
+        pub start: BlockNumber,
+        /// Last block claims are accepted in
+        pub end: BlockNumber,
+        /// Blake2x256 hash of the secret announced at the event
+        pub secret_hash: Hash,
+        /// Most badges the drop can mint
+        pub max_supply: u32,
+        /// Badges minted so far
+        pub minted: u32,
+    }
+
+    /// Event emitted when the admin registers or removes an issuer
+    #[ink(event)]
+    pub struct IssuerSet {
+        #[ink(topic)]
+        issuer: AccountId,
+        allowed: bool,
+    }
+
+    /// Event emitted when the admin adds or removes a curator
+    #[ink(event)]
+    pub struct CuratorSet {
+        #[ink(topic)]
+        curator: AccountId,
+        allowed: bool,
+    }
+
+    /// Event emitted when an account applies for creator verification
+    #[ink(event)]
+    pub struct CreatorApplied {
+        #[ink(topic)]
+        applicant: AccountId,
+        profile: Hash,
+        deposit: Balance,
+    }
+
+    /// Event emitted when a curator decides a creator application
+    #[ink(event)]
+    pub struct CreatorReviewed {
+        #[ink(topic)]
+        applicant: AccountId,
+        #[ink(topic)]
+        curator: AccountId,
+        approved: bool,
+    }
+
+    /// Event emitted when an issuer schedules an event drop
+    #[ink(event)]
+    pub struct EventDropCreated {
+        #[ink(topic)]
+        drop_id: u32,
+        #[ink(topic)]
+        schema_id: u32,
+        start: BlockNumber,
+        end: BlockNumber,
+        max_supply: u32,
+    }
+
+    /// Event emitted when an issuer publishes a schema
+    #[ink(event)]
+    pub struct SchemaCreated {
+        #[ink(topic)]
+        schema_id: u32,
+        #[ink(topic)]
+        issuer: AccountId,
+        name: String,
+    }
+
+    /// Event emitted when a badge is issued
+    #[ink(event)]
+    pub struct BadgeIssued {
+        #[ink(topic)]
+        badge_id: u32,
+        #[ink(topic)]
+        schema_id: u32,
+        #[ink(topic)]
+        owner: AccountId,
+        attestation: Hash,
+        expires_at: Option<BlockNumber>,
+    }
+
+    /// Event emitted when a holder burns a badge
+    #[ink(event)]
+    pub struct BadgeBurned {
+        #[ink(topic)]
+        badge_id: u32,
+        #[ink(topic)]
+        owner: AccountId,
+    }
+
+    /// Event emitted when an issuer revokes a badge
+    #[ink(event)]
+    pub struct BadgeRevoked {
+        #[ink(topic)]
+        badge_id: u32,
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        issuer: AccountId,
+        reason_code: u8,
+    }
+
+    /// Errors returned by the achievements contract
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Caller is not the contract admin
+        NotAdmin,
+        /// Caller is not a registered issuer, or not the schema's issuer
+        NotIssuer,
+        /// Schema does not exist
+        SchemaNotFound,
+        /// Badge does not exist
+        BadgeNotFound,
+        /// Caller does not hold the badge
+        NotOwner,
+        /// Account already holds a badge of this schema
+        AlreadyAwarded,
+        /// Account already holds `MAX_BADGES_PER_ACCOUNT` badges
+        TooManyBadges,
+        /// Expiry is not after the current block
+        InvalidExpiry,
+        /// Caller is not a curator
+        NotCurator,
+        /// Creator verification has not been enabled
+        VerificationDisabled,
+        /// Creator verification is already enabled
+        VerificationEnabled,
+        /// Payment does not match the application deposit
+        WrongDeposit,
+        /// Account already has an application pending
+        ApplicationPending,
+        /// Account has no application pending
+        NoApplication,
+        /// Returning or forwarding a deposit failed
+        TransferFailed,
+        /// Event drop does not exist
+        DropNotFound,
+        /// Drop window is empty or already over, or its supply is zero
+        InvalidDrop,
+        /// Drop is not accepting claims in this block
+        DropClosed,
+        /// Secret does not match the drop's
+        WrongSecret,
+        /// Drop has minted its whole supply
+        SoldOut,
+        /// Name or URI exceeds its length limit
+        InputTooLong,
+        /// A counter would overflow
+        Overflow,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    pub struct Achievements {
+        /// Account that registers issuers
+        admin: AccountId,
+        /// Accounts that may publish schemas and issue badges
+        issuers: Mapping<AccountId, ()>,
+        schemas: Mapping<u32, Schema>,
+        schema_count: u32,
+        badges: Mapping<u32, Badge>,
+        /// Badge ID counter
+        next_badge_id: u32,
+        /// Badge each account holds of each schema, keyed by (schema, account)
+        awarded: Mapping<(u32, AccountId), u32>,
+        /// Badge count of each account
+        balances: Mapping<AccountId, u32>,
+        /// Each account's badges by position (`0..balance`)
+        owned_badge_by_index: Mapping<(AccountId, u32), u32>,
+        /// Position of each badge in its owner's index
+        owned_badge_index: Mapping<u32, u32>,
+        /// Why and when each revoked badge was taken back
+        revocations: Mapping<u32, Revocation>,
+        /// Accounts that review creator applications
+        curators: Mapping<AccountId, ()>,
+        creator_verification: Option<CreatorVerification>,
+        /// Pending creator application of each account
+        applications: Mapping<AccountId, Application>,
+        drops: Mapping<u32, EventDrop>,
+        drop_count: u32,
+    }
+
+    impl Achievements {
+        /// Create an empty badge registry administered by the caller
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                admin: Self::env().caller(),
+                issuers: Mapping::default(),
+                schemas: Mapping::default(),
+                schema_count: 0,
+                badges: Mapping::default(),
+                next_badge_id: 1,
+                awarded: Mapping::default(),
+                balances: Mapping::default(),
+                owned_badge_by_index: Mapping::default(),
+                owned_badge_index: Mapping::default(),
+                revocations: Mapping::default(),
+                curators: Mapping::default(),
+                creator_verification: None,
+                applications: Mapping::default(),
+                drops: Mapping::default(),
+                drop_count: 0,
+            }
+        }
+
+        /// Get the admin account
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// Check whether an account may publish schemas and issue badges
+        #[ink(message)]
+        pub fn is_issuer(&self, account: AccountId) -> bool {
+            self.issuers.contains(account)
+        }
+
+        /// Register or remove an issuer (admin only). Removed issuers keep
+        /// their schemas and badges but can't issue new ones.
+        #[ink(message)]
+        pub fn set_issuer(&mut self, issuer: AccountId, allowed: bool) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAdmin);
+            }
+            if allowed {
+                self.issuers.insert(issuer, &());
+            } else {
+                self.issuers.remove(issuer);
+            }
+            self.env().emit_event(IssuerSet { issuer, allowed });
+            Ok(())
+        }
+
+        /// Get the number of schemas published
+        #[ink(message)]
+        pub fn schema_count(&self) -> u32 {
+            self.schema_count
+        }
+
+        /// Get a schema
+        #[ink(message)]
+        pub fn schema(&self, schema_id: u32) -> Option<Schema> {
+            self.schemas.get(schema_id)
+        }
+
+        /// Publish an achievement schema (issuers only)
+        #[ink(message)]
+        pub fn create_schema(&mut self, name: String, uri: String) -> Result<u32> {
+            let issuer = self.env().caller();
+            if !self.is_issuer(issuer) {
+                return Err(Error::NotIssuer);
+            }
+            if name.len() > MAX_NAME_LEN || uri.len() > MAX_URI_LEN {
+                return Err(Error::InputTooLong);
+            }
+
+            let schema_id = self.schema_count;
+            self.schema_count = schema_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.schemas.insert(
+                schema_id,
+                &Schema {
+                    issuer,
+                    name: name.clone(),
+                    uri,
+                },
+            );
+
+            self.env().emit_event(SchemaCreated { schema_id, issuer, name });
+            Ok(schema_id)
+        }
+
+        /// Get a badge
+        #[ink(message)]
+        pub fn badge(&self, badge_id: u32) -> Option<Badge> {
+            self.badges.get(badge_id)
+        }
+
+        /// Get the badge an account holds of a schema
+        #[ink(message)]
+        pub fn badge_of_schema(&self, account: AccountId, schema_id: u32) -> Option<u32> {
+            self.awarded.get((schema_id, account))
+        }
+
+        /// Get every badge an account holds, with its ID
+        #[ink(message)]
+        pub fn badges_of(&self, account: AccountId) -> Vec<(u32, Badge)> {
+            self.tokens_of_owner(account)
+                .into_iter()
+                .filter_map(|badge_id| Some((badge_id, self.badges.get(badge_id)?)))
+                .collect()
+        }
+
+        /// Check whether a badge exists, hasn't been revoked and hasn't expired
+        #[ink(message)]
+        pub fn is_valid(&self, badge_id: u32) -> bool {
+            !self.is_revoked(badge_id)
+                && self
+                    .badges
+                    .get(badge_id)
+                    .is_some_and(|badge| !self.is_expired(&badge))
+        }
+
+        /// Award a schema's badge to an account (the schema's issuer only,
+        /// while registered). `attestation` is the hash of the evidence and
+        /// `expires_at` the first block the badge stops being valid in. An
+        /// expired badge of the same schema is replaced, so passes renew by
+        /// issuing again.
+        #[ink(message)]
+        pub fn issue(
+            &mut self,
+            to: AccountId,
+            schema_id: u32,
+            attestation: Hash,
+            expires_at: Option<BlockNumber>,
+        ) -> Result<u32> {
+            let issuer = self.env().caller();
+            let schema = self.schemas.get(schema_id).ok_or(Error::SchemaNotFound)?;
+            if schema.issuer != issuer || !self.is_issuer(issuer) {
+                return Err(Error::NotIssuer);
+            }
+            if expires_at.is_some_and(|expiry| expiry <= self.env().block_number()) {
+                return Err(Error::InvalidExpiry);
+            }
+            self.mint_badge(to, schema_id, attestation, expires_at)
+        }
+
+        /// Award a badge, replacing an expired one of the same schema
+        fn mint_badge(
+            &mut self,
+            to: AccountId,
+            schema_id: u32,
+            attestation: Hash,
+            expires_at: Option<BlockNumber>,
+        ) -> Result<u32> {
+            if let Some(held) = self.awarded.get((schema_id, to)) {
+                match self.badges.get(held) {
+                    Some(badge) if self.is_expired(&badge) => self.remove_badge(held, &badge),
+                    _ => return Err(Error::AlreadyAwarded),
+                }
+            }
+            if self.balance_of(to) >= MAX_BADGES_PER_ACCOUNT {
+                return Err(Error::TooManyBadges);
+            }
+
+            let badge_id = self.next_badge_id;
+            self.next_badge_id = badge_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.index().push(to, badge_id).ok_or(Error::Overflow)?;
+            self.awarded.insert((schema_id, to), &badge_id);
+            self.badges.insert(
+                badge_id,
+                &Badge {
+                    schema_id,
+                    owner: to,
+                    attestation,
+                    issued_at: self.env().block_number(),
+                    expires_at,
+                },
+            );
+
+            self.env().emit_event(BadgeIssued {
+                badge_id,
+                schema_id,
+                owner: to,
+                attestation,
+                expires_at,
+            });
+            Ok(badge_id)
+        }
+
+        /// Destroy a badge the caller holds
+        #[ink(message)]
+        pub fn burn(&mut self, badge_id: u32) -> Result<()> {
+            let badge = self.badges.get(badge_id).ok_or(Error::BadgeNotFound)?;
+            if badge.owner != self.env().caller() {
+                return Err(Error::NotOwner);
+            }
+            self.remove_badge(badge_id, &badge);
+            self.env().emit_event(BadgeBurned {
+                badge_id,
+                owner: badge.owner,
+            });
+            Ok(())
+        }
+
+        /// Take back a badge, e.g. a lapsed moderator role (the schema's
+        /// issuer only, even once deregistered, or a curator for verification
+        /// badges). `reason_code` is the issuer's own code for why.
+        #[ink(message)]
+        pub fn revoke(&mut self, badge_id: u32, reason_code: u8) -> Result<()> {
+            let issuer = self.env().caller();
+            let badge = self.badges.get(badge_id).ok_or(Error::BadgeNotFound)?;
+            let schema = self.schemas.get(badge.schema_id).ok_or(Error::SchemaNotFound)?;
+            let curated = schema.issuer == self.env().account_id() && self.is_curator(issuer);
+            if schema.issuer != issuer && !curated {
+                return Err(Error::NotIssuer);
+            }
+
+            self.remove_badge(badge_id, &badge);
+            self.revocations.insert(
+                badge_id,
+                &Revocation {
+                    reason_code,
+                    revoked_at: self.env().block_number(),
+                },
+            );
+            self.env().emit_event(BadgeRevoked {
+                badge_id,
+                owner: badge.owner,
+                issuer,
+                reason_code,
+            });
+            Ok(())
+        }
+
+        /// Check whether a badge was revoked by its issuer
+        #[ink(message)]
+        pub fn is_revoked(&self, badge_id: u32) -> bool {
+            self.revocations.contains(badge_id)
+        }
+
+        /// Get why and when a badge was revoked
+        #[ink(message)]
+        pub fn revocation(&self, badge_id: u32) -> Option<Revocation> {
+            self.revocations.get(badge_id)
+        }
+
+        /// Check whether an account reviews creator applications
+        #[ink(message)]
+        pub fn is_curator(&self, account: AccountId) -> bool {
+            self.curators.contains(account)
+        }
+
+        /// Add or remove a curator (admin only)
+        #[ink(message)]
+        pub fn set_curator(&mut self, curator: AccountId, allowed: bool) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAdmin);
+            }
+            if allowed {
+                self.curators.insert(curator, &());
+            } else {
+                self.curators.remove(curator);
+            }
+            self.env().emit_event(CuratorSet { curator, allowed });
+            Ok(())
+        }
+
+        /// Get the creator verification settings, if enabled
+        #[ink(message)]
+        pub fn creator_verification(&self) -> Option<CreatorVerification> {
+            self.creator_verification
+        }
+
+        /// Turn on creator verification (admin only, once), publishing the
+        /// verification badge's schema with the contract as its issuer
+        #[ink(message)]
+        pub fn enable_creator_verification(
+            &mut self,
+            name: String,
+            uri: String,
+            deposit: Balance,
+        ) -> Result<u32> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAdmin);
+            }
+            if self.creator_verification.is_some() {
+                return Err(Error::VerificationEnabled);
+            }
+            if name.len() > MAX_NAME_LEN || uri.len() > MAX_URI_LEN {
+                return Err(Error::InputTooLong);
+            }
+
+            let issuer = self.env().account_id();
+            let schema_id = self.schema_count;
+            self.schema_count = schema_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.schemas.insert(
+                schema_id,
+                &Schema {
+                    issuer,
+                    name: name.clone(),
+                    uri,
+                },
+            );
+            self.creator_verification = Some(CreatorVerification { schema_id, deposit });
+
+            self.env().emit_event(SchemaCreated { schema_id, issuer, name });
+            Ok(schema_id)
+        }
+
+        /// Get an account's pending creator application
+        #[ink(message)]
+        pub fn application(&self, applicant: AccountId) -> Option<Application> {
+            self.applications.get(applicant)
+        }
+
+        /// Check whether an account holds a valid creator verification badge
+        #[ink(message)]
+        pub fn is_verified_creator(&self, account: AccountId) -> bool {
+            self.creator_verification
+                .and_then(|verification| self.awarded.get((verification.schema_id, account)))
+                .is_some_and(|badge_id| self.is_valid(badge_id))
+        }
+
+        /// Apply for creator verification, paying exactly the deposit.
+        /// `profile` is the hash of the creator's off-chain profile.
+        #[ink(message, payable)]
+        pub fn apply_creator(&mut self, profile: Hash) -> Result<()> {
+            let applicant = self.env().caller();
+            let verification = self.creator_verification.ok_or(Error::VerificationDisabled)?;
+            let deposit = self.env().transferred_value();
+            if deposit != verification.deposit {
+                return Err(Error::WrongDeposit);
+            }
+            if self.applications.contains(applicant) {
+                return Err(Error::ApplicationPending);
+            }
+            if self.is_verified_creator(applicant) {
+                return Err(Error::AlreadyAwarded);
+            }
+
+            self.applications.insert(
+                applicant,
+                &Application {
+                    profile,
+                    deposit,
+                    applied_at: self.env().block_number(),
+                },
+            );
+            self.env().emit_event(CreatorApplied {
+                applicant,
+                profile,
+                deposit,
+            });
+            Ok(())
+        }
+
+        /// Decide a creator application (curators only). Approval issues the
+        /// verification badge, attested by the profile hash, and returns the
+        /// deposit; rejection sends the deposit to the admin.
+        #[ink(message)]
+        pub fn review_creator(&mut self, applicant: AccountId, approve: bool) -> Result<Option<u32>> {
+            let curator = self.env().caller();
+            if !self.is_curator(curator) {
+                return Err(Error::NotCurator);
+            }
+            let verification = self.creator_verification.ok_or(Error::VerificationDisabled)?;
+            let application = self.applications.get(applicant).ok_or(Error::NoApplication)?;
+            self.applications.remove(applicant);
+
+            let badge_id = if approve {
+                Some(self.mint_badge(applicant, verification.schema_id, application.profile, None)?)
+            } else {
+                None
+            };
+            let payee = if approve { applicant } else { self.admin };
+            if application.deposit > 0 && self.env().transfer(payee, application.deposit).is_err() {
+                return Err(Error::TransferFailed);
+            }
+
+            self.env().emit_event(CreatorReviewed {
+                applicant,
+                curator,
+                approved: approve,
+            });
+            Ok(badge_id)
+        }
+
+        /// Get the number of event drops created
+        #[ink(message)]
+        pub fn event_drop_count(&self) -> u32 {
+            self.drop_count
+        }
+
+        /// Get an event drop
+        #[ink(message)]
+        pub fn event_drop(&self, drop_id: u32) -> Option<EventDrop> {
+            self.drops.get(drop_id)
+        }
+
+        /// Schedule a proof-of-attendance drop of a schema's badge (the
+        /// schema's issuer only, while registered). Claims are accepted from
+        /// `start` through `end`; `secret_hash` is the Blake2x256 hash of the
+        /// secret attendees will be given.
+        #[ink(message)]
+        pub fn create_event_drop(
+            &mut self,
+            schema_id: u32,
+            start: BlockNumber,
+            end: BlockNumber,
+            secret_hash: Hash,
+            max_supply: u32,
+        ) -> Result<u32> {
+            let issuer = self.env().caller();
+            let schema = self.schemas.get(schema_id).ok_or(Error::SchemaNotFound)?;
+            if schema.issuer != issuer || !self.is_issuer(issuer) {
+                return Err(Error::NotIssuer);
+            }
+            if end < start || end < self.env().block_number() || max_supply == 0 {
+                return Err(Error::InvalidDrop);
+            }
+
+            let drop_id = self.drop_count;
+            self.drop_count = drop_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.drops.insert(
+                drop_id,
+                &EventDrop {
+                    schema_id,
+                    start,
+                    end,
+                    secret_hash,
+                    max_supply,
+                    minted: 0,
+                },
+            );
+
+            self.env().emit_event(EventDropCreated {
+                drop_id,
+                schema_id,
+                start,
+                end,
+                max_supply,
+            });
+            Ok(drop_id)
+        }
+
+        /// Claim an event drop's badge with the event's secret. Each account
+        /// holds one badge per schema, so drops sharing a schema pay out once.
+        #[ink(message)]
+        pub fn claim_event_drop(&mut self, drop_id: u32, secret: Vec<u8>) -> Result<u32> {
+            let mut event_drop = self.drops.get(drop_id).ok_or(Error::DropNotFound)?;
+            let now = self.env().block_number();
+            if now < event_drop.start || now > event_drop.end {
+                return Err(Error::DropClosed);
+            }
+            let mut hash = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&secret, &mut hash);
+            if Hash::from(hash) != event_drop.secret_hash {
+                return Err(Error::WrongSecret);
+            }
+            if event_drop.minted >= event_drop.max_supply {
+                return Err(Error::SoldOut);
+            }
+            let schema = self.schemas.get(event_drop.schema_id).ok_or(Error::SchemaNotFound)?;
+            if !self.is_issuer(schema.issuer) {
+                return Err(Error::NotIssuer);
+            }
+
+            let attendee = self.env().caller();
+            let badge_id = self.mint_badge(attendee, event_drop.schema_id, event_drop.secret_hash, None)?;
+            event_drop.minted = event_drop.minted.saturating_add(1);
+            self.drops.insert(drop_id, &event_drop);
+            Ok(badge_id)
+        }
+
+        /// Whether a badge's expiry has passed
+        fn is_expired(&self, badge: &Badge) -> bool {
+            badge
+                .expires_at
+                .is_some_and(|expiry| self.env().block_number() >= expiry)
+        }
+
+        /// Drop a badge and its owner's bookkeeping
+        fn remove_badge(&mut self, badge_id: u32, badge: &Badge) {
+            self.index().remove(badge.owner, badge_id);
+            self.awarded.remove((badge.schema_id, badge.owner));
+            self.badges.remove(badge_id);
+        }
+
+        /// Borrow the owner index over its three mappings
+        fn index(&mut self) -> OwnerIndex<'_, impl StorageKey, impl StorageKey, impl StorageKey> {
+            OwnerIndex {
+                balances: &mut self.balances,
+                tokens: &mut self.owned_badge_by_index,
+                positions: &mut self.owned_badge_index,
+            }
+        }
+    }
+
+    impl Default for Achievements {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl PSP34Core for Achievements {
+        /// Get the number of badges held by `owner`
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u32 {
+            self.balances.get(owner).unwrap_or(0)
+        }
+
+        /// Get the holder of a badge
+        #[ink(message)]
+        fn owner_of(&self, token_id: u32) -> Option<AccountId> {
+            self.badges.get(token_id).map(|badge| badge.owner)
+        }
+
+        /// Check whether a badge has been issued and not burned or revoked
+        #[ink(message)]
+        fn exists(&self, token_id: u32) -> bool {
+            self.badges.contains(token_id)
+        }
+    }
+
+    impl Enumerable for Achievements {
+        /// Get all badges held by an address
+        #[ink(message)]
+        fn tokens_of_owner(&self, owner: AccountId) -> Vec<u32> {
+            (0..self.balance_of(owner))
+                .filter_map(|index| self.owned_badge_by_index.get((owner, index)))
+                .collect()
+        }
+
+        /// Get the badge at `index` of an owner's list (`index < balance_of(owner)`)
+        #[ink(message)]
+        fn token_of_owner_by_index(&self, owner: AccountId, index: u32) -> Option<u32> {
+            self.owned_badge_by_index.get((owner, index))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        fn new_registry() -> Achievements {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut registry = Achievements::new();
+            registry.set_issuer(accounts.bob, true).unwrap();
+            registry
+        }
+
+        #[ink::test]
+        fn issuers_award_badges_once_per_schema() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut registry = new_registry();
+            assert_eq!(
+                registry.create_schema(String::from("First Summit"), String::from("ipfs://QmSummit")),
+                Err(Error::NotIssuer)
+            );
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let summit = registry
+                .create_schema(String::from("First Summit"), String::from("ipfs://QmSummit"))
+                .unwrap();
+            let event = registry
+                .create_schema(String::from("Launch Party"), String::from("ipfs://QmParty"))
+                .unwrap();
+            let proof = Hash::from([3; 32]);
+            let badge = registry.issue(accounts.charlie, summit, proof, None).unwrap();
+            assert_eq!(registry.issue(accounts.charlie, summit, proof, None), Err(Error::AlreadyAwarded));
+            let second = registry.issue(accounts.charlie, event, proof, None).unwrap();
+
+            assert_eq!(registry.badge_of_schema(accounts.charlie, summit), Some(badge));
+            assert_eq!(registry.owner_of(badge), Some(accounts.charlie));
+            let badges = registry.badges_of(accounts.charlie);
+            assert_eq!(badges.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![badge, second]);
+            assert_eq!(badges[0].1.attestation, proof);
+
+            // Only the schema's issuer, while registered, issues its badges
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            registry.set_issuer(accounts.eve, true).unwrap();
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(registry.issue(accounts.django, summit, proof, None), Err(Error::NotIssuer));
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            registry.set_issuer(accounts.bob, false).unwrap();
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.issue(accounts.django, summit, proof, None), Err(Error::NotIssuer));
+        }
+
+        #[ink::test]
+        fn holders_burn_and_issuers_revoke() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut registry = new_registry();
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let schema = registry
+                .create_schema(String::from("Builder"), String::from("ipfs://QmBuilder"))
+                .unwrap();
+            let kept = registry.issue(accounts.charlie, schema, Hash::from([1; 32]), None).unwrap();
+            let burned = registry.issue(accounts.django, schema, Hash::from([2; 32]), None).unwrap();
+
+            assert_eq!(registry.burn(kept), Err(Error::NotOwner));
+            test::set_caller::<DefaultEnvironment>(accounts.django);
+            assert_eq!(registry.revoke(kept, 1), Err(Error::NotIssuer));
+            assert_eq!(registry.burn(burned), Ok(()));
+            assert!(!registry.exists(burned));
+            assert!(!registry.is_revoked(burned));
+            assert_eq!(registry.balance_of(accounts.django), 0);
+
+            // Deregistered issuers can still revoke what they issued
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            registry.set_issuer(accounts.bob, false).unwrap();
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            test::advance_block::<DefaultEnvironment>();
+            assert_eq!(registry.revoke(kept, 7), Ok(()));
+            assert!(registry.badges_of(accounts.charlie).is_empty());
+            assert!(registry.is_revoked(kept));
+            assert_eq!(
+                registry.revocation(kept),
+                Some(Revocation {
+                    reason_code: 7,
+                    revoked_at: 1,
+                })
+            );
+            assert_eq!(registry.revoke(kept, 7), Err(Error::BadgeNotFound));
+
+            // A revoked badge can be awarded again, under a new ID
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            registry.set_issuer(accounts.bob, true).unwrap();
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let reissued = registry.issue(accounts.charlie, schema, Hash::from([1; 32]), None).unwrap();
+            assert!(!registry.is_revoked(reissued));
+        }
+
+        #[ink::test]
+        fn curators_review_staked_creator_applications() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut registry = new_registry();
+            test::set_callee::<DefaultEnvironment>(accounts.frank);
+            test::set_account_balance::<DefaultEnvironment>(accounts.frank, 1_000);
+            let profile = Hash::from([5; 32]);
+            assert_eq!(registry.apply_creator(profile), Err(Error::VerificationDisabled));
+
+            let schema = registry
+                .enable_creator_verification(
+                    String::from("Verified Creator"),
+                    String::from("ipfs://QmCreator"),
+                    100,
+                )
+                .unwrap();
+            registry.set_curator(accounts.eve, true).unwrap();
+            // The schema belongs to the contract, so no issuer can mint it
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.issue(accounts.bob, schema, profile, None), Err(Error::NotIssuer));
+
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            test::set_value_transferred::<DefaultEnvironment>(50);
+            assert_eq!(registry.apply_creator(profile), Err(Error::WrongDeposit));
+            test::set_value_transferred::<DefaultEnvironment>(100);
+            assert_eq!(registry.apply_creator(profile), Ok(()));
+            assert_eq!(registry.apply_creator(profile), Err(Error::ApplicationPending));
+            test::set_caller::<DefaultEnvironment>(accounts.django);
+            assert_eq!(registry.apply_creator(profile), Ok(()));
+            test::set_value_transferred::<DefaultEnvironment>(0);
+
+            assert_eq!(registry.review_creator(accounts.charlie, true), Err(Error::NotCurator));
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            let charlie_before = test::get_account_balance::<DefaultEnvironment>(accounts.charlie).unwrap();
+            let badge = registry.review_creator(accounts.charlie, true).unwrap().unwrap();
+            assert_eq!(
+                test::get_account_balance::<DefaultEnvironment>(accounts.charlie),
+                Ok(charlie_before + 100)
+            );
+            assert!(registry.is_verified_creator(accounts.charlie));
+            assert_eq!(registry.badge(badge).unwrap().attestation, profile);
+            assert_eq!(registry.application(accounts.charlie), None);
+
+            // Rejected deposits go to the admin
+            let admin_before = test::get_account_balance::<DefaultEnvironment>(accounts.alice).unwrap();
+            assert_eq!(registry.review_creator(accounts.django, false), Ok(None));
+            assert_eq!(
+                test::get_account_balance::<DefaultEnvironment>(accounts.alice),
+                Ok(admin_before + 100)
+            );
+            assert!(!registry.is_verified_creator(accounts.django));
+            assert_eq!(registry.review_creator(accounts.django, true), Err(Error::NoApplication));
+
+            // Curators can revoke verification
+            assert_eq!(registry.revoke(badge, 2), Ok(()));
+            assert!(!registry.is_verified_creator(accounts.charlie));
+        }
+
+        #[ink::test]
+        fn attendees_claim_drops_with_the_secret() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut registry = new_registry();
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let schema = registry
+                .create_schema(String::from("Genesis Meetup"), String::from("ipfs://QmMeetup"))
+                .unwrap();
+            let secret = b"open sesame".to_vec();
+            let mut hash = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&secret, &mut hash);
+            let secret_hash = Hash::from(hash);
+            assert_eq!(
+                registry.create_event_drop(schema, 2, 1, secret_hash, 2),
+                Err(Error::InvalidDrop)
+            );
+            let drop_id = registry.create_event_drop(schema, 1, 2, secret_hash, 2).unwrap();
+
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(registry.claim_event_drop(drop_id, secret.clone()), Err(Error::DropClosed));
+            test::advance_block::<DefaultEnvironment>();
+            assert_eq!(registry.claim_event_drop(drop_id, b"guess".to_vec()), Err(Error::WrongSecret));
+            let badge = registry.claim_event_drop(drop_id, secret.clone()).unwrap();
+            assert_eq!(registry.owner_of(badge), Some(accounts.charlie));
+            assert_eq!(registry.claim_event_drop(drop_id, secret.clone()), Err(Error::AlreadyAwarded));
+
+            test::set_caller::<DefaultEnvironment>(accounts.django);
+            assert!(registry.claim_event_drop(drop_id, secret.clone()).is_ok());
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(registry.claim_event_drop(drop_id, secret.clone()), Err(Error::SoldOut));
+            assert_eq!(registry.event_drop(drop_id).unwrap().minted, 2);
+
+            test::advance_block::<DefaultEnvironment>();
+            test::advance_block::<DefaultEnvironment>();
+            assert_eq!(registry.claim_event_drop(drop_id, secret), Err(Error::DropClosed));
+        }
+
+        #[ink::test]
+        fn badges_expire_and_renew() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut registry = new_registry();
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let season = registry
+                .create_schema(String::from("Season Pass"), String::from("ipfs://QmSeason"))
+                .unwrap();
+            let proof = Hash::from([4; 32]);
+            assert_eq!(
+                registry.issue(accounts.charlie, season, proof, Some(0)),
+                Err(Error::InvalidExpiry)
+            );
+            let pass = registry.issue(accounts.charlie, season, proof, Some(2)).unwrap();
+            assert!(registry.is_valid(pass));
+
+            test::advance_block::<DefaultEnvironment>();
+            assert!(registry.is_valid(pass));
+            assert_eq!(
+                registry.issue(accounts.charlie, season, proof, Some(5)),
+                Err(Error::AlreadyAwarded)
+            );
+
+            test::advance_block::<DefaultEnvironment>();
+            assert!(!registry.is_valid(pass));
+            assert!(registry.exists(pass));
+
+            // Issuing again replaces the expired pass
+            let renewed = registry.issue(accounts.charlie, season, proof, Some(5)).unwrap();
+            assert!(!registry.exists(pass));
+            assert!(registry.is_valid(renewed));
+            assert_eq!(registry.balance_of(accounts.charlie), 1);
+            assert!(!registry.is_valid(999));
+        }
+    }
+}
@@ -0,0 +1,175 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::indexing_slicing
+    )
+)]
+
+/// VR Genesis Frame - Collection Factory
+/// Deploys new AssetHubNFT collections from an uploaded code hash, with the
+/// caller as each collection's admin, and keeps a registry of everything it
+/// has deployed so frontends can list collections without an indexer.
+#[ink::contract]
+pub mod collection_factory {
+    use asset_hub_nft_final::asset_hub_nft::{AssetHubNFTRef, CollectionConfig};
+    use ink::codegen::EmitEvent;
+    use ink::prelude::string::String;
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use ink::ToAccountId;
+    use scale::{Decode, Encode};
+
+    /// Maximum number of entries returned by paginated queries
+    pub const MAX_PAGE_SIZE: u32 = 100;
+
+    /// Event emitted when a new collection is deployed
+    #[ink(event)]
+    pub struct CollectionCreated {
+        #[ink(topic)]
+        creator: AccountId,
+        #[ink(topic)]
+        collection: AccountId,
+        /// Position of the collection in the registry
+        index: u32,
+        name: String,
+        symbol: String,
+    }
+
+    /// Errors returned by the factory
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Instantiating the collection failed before its constructor ran
+        InstantiationFailed,
+        /// The collection's constructor rejected the name, symbol or config
+        CollectionRejected,
+        /// The registry is full
+        Overflow,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    pub struct CollectionFactory {
+        /// Code hash of the uploaded AssetHubNFT contract
+        collection_code_hash: Hash,
+        /// Every collection deployed, in order
+        collections: Mapping<u32, AccountId>,
+        collection_count: u32,
+        /// Collections per creator, in order
+        creator_collections: Mapping<(AccountId, u32), AccountId>,
+        creator_collection_count: Mapping<AccountId, u32>,
+    }
+
+    impl CollectionFactory {
+        /// Create a factory deploying collections from `collection_code_hash`
+        #[ink(constructor)]
+        pub fn new(collection_code_hash: Hash) -> Self {
+            Self {
+                collection_code_hash,
+                collections: Mapping::default(),
+                collection_count: 0,
+                creator_collections: Mapping::default(),
+                creator_collection_count: Mapping::default(),
+            }
+        }
+
+        /// Get the code hash new collections are deployed from
+        #[ink(message)]
+        pub fn collection_code_hash(&self) -> Hash {
+            self.collection_code_hash
+        }
+
+        /// Get the number of collections deployed so far
+        #[ink(message)]
+        pub fn collection_count(&self) -> u32 {
+            self.collection_count
+        }
+
+        /// Get the collection at `index` of the registry
+        #[ink(message)]
+        pub fn collection_at(&self, index: u32) -> Option<AccountId> {
+            self.collections.get(index)
+        }
+
+        /// Get up to `limit` (at most `MAX_PAGE_SIZE`) collections deployed by
+        /// `creator`, starting at their `start`-th
+        #[ink(message)]
+        pub fn collections_of(&self, creator: AccountId, start: u32, limit: u32) -> Vec<AccountId> {
+            let end = start
+                .saturating_add(limit.min(MAX_PAGE_SIZE))
+                .min(self.creator_collection_count.get(creator).unwrap_or(0));
+            (start..end)
+                .filter_map(|index| self.creator_collections.get((creator, index)))
+                .collect()
+        }
+
+        /// Deploy a new collection administered by the caller. Any value
+        /// sent is passed on to the new contract as its endowment.
+        #[ink(message, payable)]
+        pub fn create_collection(&mut self, config: CollectionConfig) -> Result<AccountId> {
+            let creator = self.env().caller();
+            let index = self.collection_count;
+            let creator_index = self.creator_collection_count.get(creator).unwrap_or(0);
+            let next_index = index.checked_add(1).ok_or(Error::Overflow)?;
+            let next_creator_index = creator_index.checked_add(1).ok_or(Error::Overflow)?;
+
+            let name = config.name.clone();
+            let symbol = config.symbol.clone();
+
+            // The registry index is unique, so the salt keeps addresses apart
+            // even when the same creator reuses a config
+            let collection: AssetHubNFTRef = AssetHubNFTRef::new_with_admin(creator, config)
+                .code_hash(self.collection_code_hash)
+                .endowment(self.env().transferred_value())
+                .salt_bytes(index.to_le_bytes())
+                .try_instantiate()
+                .map_err(|_| Error::InstantiationFailed)?
+                .map_err(|_| Error::InstantiationFailed)?
+                .map_err(|_| Error::CollectionRejected)?;
+            let collection = collection.to_account_id();
+
+            self.collections.insert(index, &collection);
+            self.collection_count = next_index;
+            self.creator_collections.insert((creator, creator_index), &collection);
+            self.creator_collection_count.insert(creator, &next_creator_index);
+
+            // Spelled out because the collection crate's event impl is also in scope
+            EmitEvent::<CollectionFactory>::emit_event(
+                self.env(),
+                CollectionCreated {
+                    creator,
+                    collection,
+                    index,
+                    name,
+                    symbol,
+                },
+            );
+
+            Ok(collection)
+        }
+    }
+
+    /// Unit tests. The off-chain environment can't instantiate contracts,
+    /// so deployment itself is only exercised on a node.
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        #[ink::test]
+        fn new_factory_is_empty() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let code_hash = Hash::from([7u8; 32]);
+            let factory = CollectionFactory::new(code_hash);
+            assert_eq!(factory.collection_code_hash(), code_hash);
+            assert_eq!(factory.collection_count(), 0);
+            assert_eq!(factory.collection_at(0), None);
+            assert!(factory.collections_of(accounts.alice, 0, MAX_PAGE_SIZE).is_empty());
+        }
+    }
+}
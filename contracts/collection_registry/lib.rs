@@ -0,0 +1,332 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::indexing_slicing
+    )
+)]
+
+/// VR Genesis Frame - Collection Registry
+/// Collection owners register their contract address with display metadata,
+/// and curators mark entries as verified or flagged. Marketplaces and the VR
+/// client read this registry to decide which contracts to trust.
+/// Registration is open to anyone, so only `Verified` entries should be
+/// treated as legitimate; curators flag squatted or malicious ones.
+#[ink::contract]
+pub mod collection_registry {
+    use ink::prelude::string::String;
+    use ink::storage::Mapping;
+    use scale::{Decode, Encode};
+
+    /// Maximum length of a collection name, in bytes
+    pub const MAX_NAME_LEN: usize = 64;
+
+    /// Maximum length of a collection symbol, in bytes
+    pub const MAX_SYMBOL_LEN: usize = 16;
+
+    /// Maximum length of a metadata URI, in bytes
+    pub const MAX_URI_LEN: usize = 256;
+
+    /// Curation state of a registered collection
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum Status {
+        /// Registered but not yet reviewed, or changed since review
+        Unverified,
+        /// Reviewed and confirmed legitimate by a curator
+        Verified,
+        /// Marked by a curator as squatted, fraudulent or otherwise unsafe
+        Flagged,
+    }
+
+    /// Display metadata supplied by the collection owner
+    #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct CollectionMetadata {
+        pub name: String,
+        pub symbol: String,
+        /// URI of the collection's off-chain description
+        pub uri: String,
+    }
+
+    /// A registered collection
+    #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Entry {
+        /// Account that registered the collection and may edit its metadata
+        pub owner: AccountId,
+        pub metadata: CollectionMetadata,
+        pub status: Status,
+    }
+
+    /// Event emitted when a collection is registered
+    #[ink(event)]
+    pub struct CollectionRegistered {
+        #[ink(topic)]
+        collection: AccountId,
+        #[ink(topic)]
+        owner: AccountId,
+    }
+
+    /// Event emitted when a collection's metadata changes
+    #[ink(event)]
+    pub struct MetadataUpdated {
+        #[ink(topic)]
+        collection: AccountId,
+    }
+
+    /// Event emitted when a curator changes a collection's status
+    #[ink(event)]
+    pub struct StatusChanged {
+        #[ink(topic)]
+        collection: AccountId,
+        #[ink(topic)]
+        curator: AccountId,
+        status: Status,
+    }
+
+    /// Errors returned by the registry
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// The collection is already registered
+        AlreadyRegistered,
+        /// The collection is not registered
+        NotRegistered,
+        /// Caller did not register the collection
+        NotOwner,
+        /// Caller is not a curator
+        NotCurator,
+        /// Caller is not the registry admin
+        NotAdmin,
+        /// Name, symbol or URI exceeds its length limit
+        InputTooLong,
+        /// The registry is full
+        Overflow,
+        /// Flagged collections can't be edited by their owner
+        Flagged,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    pub struct CollectionRegistry {
+        /// Account that appoints curators
+        admin: AccountId,
+        curators: Mapping<AccountId, ()>,
+        entries: Mapping<AccountId, Entry>,
+        /// Registered collections, in registration order
+        collections: Mapping<u32, AccountId>,
+        collection_count: u32,
+    }
+
+    impl CollectionRegistry {
+        /// Create a registry administered by the caller
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                admin: Self::env().caller(),
+                curators: Mapping::default(),
+                entries: Mapping::default(),
+                collections: Mapping::default(),
+                collection_count: 0,
+            }
+        }
+
+        /// Get the admin account
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// Check whether an account may change collection statuses
+        #[ink(message)]
+        pub fn is_curator(&self, account: AccountId) -> bool {
+            account == self.admin || self.curators.contains(account)
+        }
+
+        /// Get a registered collection
+        #[ink(message)]
+        pub fn entry(&self, collection: AccountId) -> Option<Entry> {
+            self.entries.get(collection)
+        }
+
+        /// Get a collection's status, or `None` if it isn't registered
+        #[ink(message)]
+        pub fn status_of(&self, collection: AccountId) -> Option<Status> {
+            self.entries.get(collection).map(|entry| entry.status)
+        }
+
+        /// Check whether a collection has been verified by a curator
+        #[ink(message)]
+        pub fn is_verified(&self, collection: AccountId) -> bool {
+            self.status_of(collection) == Some(Status::Verified)
+        }
+
+        /// Get the number of registered collections
+        #[ink(message)]
+        pub fn collection_count(&self) -> u32 {
+            self.collection_count
+        }
+
+        /// Get the collection at `index` of the registration order
+        #[ink(message)]
+        pub fn collection_at(&self, index: u32) -> Option<AccountId> {
+            self.collections.get(index)
+        }
+
+        /// Grant or revoke the curator role (admin only)
+        #[ink(message)]
+        pub fn set_curator(&mut self, account: AccountId, allowed: bool) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAdmin);
+            }
+            if allowed {
+                self.curators.insert(account, &());
+            } else {
+                self.curators.remove(account);
+            }
+            Ok(())
+        }
+
+        /// Register a collection contract, with the caller as its owner
+        #[ink(message)]
+        pub fn register(&mut self, collection: AccountId, metadata: CollectionMetadata) -> Result<()> {
+            if self.entries.contains(collection) {
+                return Err(Error::AlreadyRegistered);
+            }
+            Self::ensure_metadata_len(&metadata)?;
+            let index = self.collection_count;
+            let next_index = index.checked_add(1).ok_or(Error::Overflow)?;
+
+            let owner = self.env().caller();
+            self.entries.insert(collection, &Entry { owner, metadata, status: Status::Unverified });
+            self.collections.insert(index, &collection);
+            self.collection_count = next_index;
+
+            self.env().emit_event(CollectionRegistered { collection, owner });
+            Ok(())
+        }
+
+        /// Replace a collection's metadata (owner only). Verified collections
+        /// drop back to unverified until a curator reviews the change.
+        #[ink(message)]
+        pub fn update_metadata(&mut self, collection: AccountId, metadata: CollectionMetadata) -> Result<()> {
+            let mut entry = self.entries.get(collection).ok_or(Error::NotRegistered)?;
+            if entry.owner != self.env().caller() {
+                return Err(Error::NotOwner);
+            }
+            if entry.status == Status::Flagged {
+                return Err(Error::Flagged);
+            }
+            Self::ensure_metadata_len(&metadata)?;
+
+            entry.metadata = metadata;
+            entry.status = Status::Unverified;
+            self.entries.insert(collection, &entry);
+
+            self.env().emit_event(MetadataUpdated { collection });
+            Ok(())
+        }
+
+        /// Mark a collection as verified, flagged or unverified (curators only)
+        #[ink(message)]
+        pub fn set_status(&mut self, collection: AccountId, status: Status) -> Result<()> {
+            let curator = self.env().caller();
+            if !self.is_curator(curator) {
+                return Err(Error::NotCurator);
+            }
+            let mut entry = self.entries.get(collection).ok_or(Error::NotRegistered)?;
+
+            entry.status = status;
+            self.entries.insert(collection, &entry);
+
+            self.env().emit_event(StatusChanged { collection, curator, status });
+            Ok(())
+        }
+
+        /// Check metadata against the length limits
+        fn ensure_metadata_len(metadata: &CollectionMetadata) -> Result<()> {
+            if metadata.name.len() > MAX_NAME_LEN
+                || metadata.symbol.len() > MAX_SYMBOL_LEN
+                || metadata.uri.len() > MAX_URI_LEN
+            {
+                return Err(Error::InputTooLong);
+            }
+            Ok(())
+        }
+    }
+
+    impl Default for CollectionRegistry {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    /// Unit tests
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        fn metadata(name: &str) -> CollectionMetadata {
+            CollectionMetadata {
+                name: String::from(name),
+                symbol: String::from("VRGF"),
+                uri: String::from("ipfs://QmCollection"),
+            }
+        }
+
+        #[ink::test]
+        fn register_and_verify() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut registry = CollectionRegistry::new();
+            let collection = accounts.frank;
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.register(collection, metadata("Genesis")), Ok(()));
+            assert_eq!(registry.register(collection, metadata("Squatter")), Err(Error::AlreadyRegistered));
+            assert_eq!(registry.collection_count(), 1);
+            assert_eq!(registry.collection_at(0), Some(collection));
+            assert_eq!(registry.status_of(collection), Some(Status::Unverified));
+
+            // Only curators set the status
+            assert_eq!(registry.set_status(collection, Status::Verified), Err(Error::NotCurator));
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(registry.set_curator(accounts.charlie, true), Ok(()));
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(registry.set_status(collection, Status::Verified), Ok(()));
+            assert!(registry.is_verified(collection));
+            assert_eq!(test::recorded_events().count(), 2);
+        }
+
+        #[ink::test]
+        fn metadata_changes_need_review() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut registry = CollectionRegistry::new();
+            let collection = accounts.frank;
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.register(collection, metadata("Genesis")), Ok(()));
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(registry.set_status(collection, Status::Verified), Ok(()));
+
+            // Only the owner edits, and an edit clears verification
+            assert_eq!(registry.update_metadata(collection, metadata("Hijack")), Err(Error::NotOwner));
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let long_name = "x".repeat(MAX_NAME_LEN + 1);
+            assert_eq!(registry.update_metadata(collection, metadata(&long_name)), Err(Error::InputTooLong));
+            assert_eq!(registry.update_metadata(collection, metadata("Genesis II")), Ok(()));
+            assert_eq!(registry.status_of(collection), Some(Status::Unverified));
+            assert_eq!(registry.entry(collection).map(|e| e.metadata.name), Some(String::from("Genesis II")));
+
+            // Flagged collections stay as the curator left them
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(registry.set_status(collection, Status::Flagged), Ok(()));
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.update_metadata(collection, metadata("Genesis III")), Err(Error::Flagged));
+        }
+    }
+}
@@ -0,0 +1,123 @@
+// Gas regression guard shared by the repo's ink_e2e gas benches.
+//
+// `include!`d into each contract's `e2e_benches` module. A bench collects
+// one `Sample` per dry-run message, writes them out as a report, and checks
+// them against a baseline checked in under `golden/gas/`. A sample whose
+// `ref_time` or `proof_size` grew by more than `$GAS_TOLERANCE` percent
+// (default 5) over its baseline fails the bench. Rerun with
+// `UPDATE_GOLDEN=1` to accept new numbers, and commit the baseline.
+
+/// Percent a sample may exceed its baseline by, unless `$GAS_TOLERANCE` says otherwise
+const DEFAULT_GAS_TOLERANCE: u64 = 5;
+
+/// Gas one dry run of a message consumed
+struct Sample {
+    /// Message, with any argument that sets its cost
+    message: &'static str,
+    /// Size of the state the message ran against, such as owned tokens
+    size: u32,
+    ref_time: u64,
+    proof_size: u64,
+}
+
+/// One entry per line, so baselines diff cleanly
+fn samples_to_json(samples: &[Sample]) -> String {
+    let entries = samples
+        .iter()
+        .map(|s| {
+            format!(
+                "  {{ \"message\": \"{}\", \"size\": {}, \"ref_time\": {}, \"proof_size\": {} }}",
+                s.message, s.size, s.ref_time, s.proof_size
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("[\n{}\n]\n", entries.join(",\n"))
+}
+
+/// Value of `key` on a line written by `samples_to_json`
+fn json_field<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let start = line.find(&format!("\"{}\": ", key))? + key.len() + 4;
+    let rest = line.get(start..)?;
+    let end = rest.find([',', ' ']).unwrap_or(rest.len());
+    Some(rest[..end].trim_matches('"'))
+}
+
+/// `(message, size, ref_time, proof_size)` of every entry in a baseline
+fn parse_baseline(json: &str) -> Vec<(String, u32, u64, u64)> {
+    json.lines()
+        .filter_map(|line| {
+            Some((
+                json_field(line, "message")?.to_string(),
+                json_field(line, "size")?.parse().ok()?,
+                json_field(line, "ref_time")?.parse().ok()?,
+                json_field(line, "proof_size")?.parse().ok()?,
+            ))
+        })
+        .collect()
+}
+
+/// Print the samples and write them to `$GAS_REPORT`, or `default_path`
+fn write_report(samples: &[Sample], default_path: &str) -> std::io::Result<()> {
+    let report = samples_to_json(samples);
+    print!("{}", report);
+    let path = std::env::var("GAS_REPORT").unwrap_or_else(|_| default_path.into());
+    std::fs::write(path, report)
+}
+
+/// Fail if any sample costs more than its baseline at `path` allows.
+/// A missing baseline is recorded rather than checked, so a new bench or
+/// machine starts from its own first run.
+fn check_gas_baseline(samples: &[Sample], path: &str) {
+    let current = samples_to_json(samples);
+    let baseline = match std::fs::read_to_string(path) {
+        Ok(baseline) if std::env::var_os("UPDATE_GOLDEN").is_none() => baseline,
+        _ => {
+            if let Some(dir) = std::path::Path::new(path).parent() {
+                std::fs::create_dir_all(dir).unwrap();
+            }
+            std::fs::write(path, current).unwrap();
+            println!("recorded gas baseline {}; commit it to guard against regressions", path);
+            return;
+        }
+    };
+    let tolerance = std::env::var("GAS_TOLERANCE")
+        .ok()
+        .and_then(|percent| percent.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_GAS_TOLERANCE);
+    let baseline = parse_baseline(&baseline);
+
+    let mut regressions = Vec::new();
+    for sample in samples {
+        let Some((_, _, ref_time, proof_size)) = baseline
+            .iter()
+            .find(|(message, size, ..)| message == sample.message && *size == sample.size)
+        else {
+            println!("no gas baseline for {} at size {}", sample.message, sample.size);
+            continue;
+        };
+        for (metric, now, then) in [
+            ("ref_time", sample.ref_time, *ref_time),
+            ("proof_size", sample.proof_size, *proof_size),
+        ] {
+            if u128::from(now) * 100 > u128::from(then) * u128::from(100 + tolerance) {
+                regressions.push(format!(
+                    "  {} at size {}: {} {} -> {}",
+                    sample.message, sample.size, metric, then, now
+                ));
+            } else if u128::from(now) * u128::from(100 + tolerance) < u128::from(then) * 100 {
+                println!(
+                    "{} at size {} got cheaper: {} {} -> {}; consider refreshing {}",
+                    sample.message, sample.size, metric, then, now, path
+                );
+            }
+        }
+    }
+    assert!(
+        regressions.is_empty(),
+        "gas grew more than {}% over {}:\n{}\n\
+         If that's intended, rerun with UPDATE_GOLDEN=1 and commit the new baseline.",
+        tolerance,
+        path,
+        regressions.join("\n")
+    );
+}
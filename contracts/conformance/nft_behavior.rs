@@ -0,0 +1,95 @@
+// Shared behaviour suite for the repo's NFT contracts.
+//
+// `include!`d into each contract's unit tests next to an `NftHarness`
+// adapter, so the same scenarios run against every implementation and any
+// drift in mint or transfer rules fails the contract that diverged. Only
+// `ink::env::test` APIs common to ink! 4 and 5 are used here.
+
+use ink::env::{test, DefaultEnvironment};
+
+/// Thin adapter over one contract's mint/transfer surface
+trait NftHarness: Sized {
+    /// Contract name shown in failure messages
+    const NAME: &'static str;
+    /// Whether the contract can transfer tokens at all
+    const HAS_TRANSFER: bool = true;
+
+    /// Deploy a fresh instance with the default `alice` as deployer
+    fn deploy() -> Self;
+    /// Mint a token to `to` on behalf of the deployer, if minting succeeds
+    fn mint(&mut self, to: AccountId) -> Option<u32>;
+    /// Transfer as the current caller; `true` on success
+    fn transfer(&mut self, to: AccountId, token_id: u32) -> bool;
+    fn owner_of(&self, token_id: u32) -> Option<AccountId>;
+    /// `None` if the contract doesn't track balances
+    fn balance_of(&self, owner: AccountId) -> Option<u32>;
+}
+
+fn assert_balance<N: NftHarness>(nft: &N, owner: AccountId, expected: u32) {
+    if let Some(balance) = nft.balance_of(owner) {
+        assert_eq!(balance, expected, "{}: balance", N::NAME);
+    }
+}
+
+fn mint_assigns_distinct_ids<N: NftHarness>() {
+    let accounts = test::default_accounts::<DefaultEnvironment>();
+    let mut nft = N::deploy();
+
+    let first = nft.mint(accounts.bob).expect("mint failed");
+    let second = nft.mint(accounts.bob).expect("mint failed");
+    assert_ne!(first, second, "{}: reused token ID", N::NAME);
+    assert_eq!(nft.owner_of(first), Some(accounts.bob), "{}: owner after mint", N::NAME);
+    assert_eq!(nft.owner_of(second.wrapping_add(1)), None, "{}: unminted token has an owner", N::NAME);
+    assert_balance(&nft, accounts.bob, 2);
+}
+
+fn owner_can_transfer<N: NftHarness>() {
+    let accounts = test::default_accounts::<DefaultEnvironment>();
+    let mut nft = N::deploy();
+    let token_id = nft.mint(accounts.bob).expect("mint failed");
+
+    test::set_caller::<DefaultEnvironment>(accounts.bob);
+    assert!(nft.transfer(accounts.charlie, token_id), "{}: owner transfer failed", N::NAME);
+    assert_eq!(nft.owner_of(token_id), Some(accounts.charlie), "{}: owner after transfer", N::NAME);
+    assert_balance(&nft, accounts.bob, 0);
+    assert_balance(&nft, accounts.charlie, 1);
+}
+
+fn invalid_transfers_change_nothing<N: NftHarness>() {
+    let accounts = test::default_accounts::<DefaultEnvironment>();
+    let mut nft = N::deploy();
+    let token_id = nft.mint(accounts.bob).expect("mint failed");
+
+    let rejected = [
+        // Not the owner
+        (accounts.charlie, accounts.charlie, token_id),
+        // Unknown token
+        (accounts.bob, accounts.charlie, token_id.wrapping_add(1)),
+        // To the current owner
+        (accounts.bob, accounts.bob, token_id),
+        // To the zero address
+        (accounts.bob, AccountId::from([0u8; 32]), token_id),
+    ];
+    for (caller, to, id) in rejected {
+        test::set_caller::<DefaultEnvironment>(caller);
+        assert!(!nft.transfer(to, id), "{}: accepted transfer of {} to {:?}", N::NAME, id, to);
+        assert_eq!(nft.owner_of(token_id), Some(accounts.bob), "{}: owner changed", N::NAME);
+        assert_balance(&nft, accounts.bob, 1);
+    }
+}
+
+/// Run every scenario against `N`, each in a fresh off-chain environment
+fn run_behavior_suite<N: NftHarness>() {
+    let mut scenarios: Vec<fn()> = vec![mint_assigns_distinct_ids::<N>];
+    if N::HAS_TRANSFER {
+        scenarios.push(owner_can_transfer::<N>);
+        scenarios.push(invalid_transfers_change_nothing::<N>);
+    }
+    for scenario in scenarios {
+        test::run_test::<DefaultEnvironment, _>(|_| {
+            scenario();
+            Ok(())
+        })
+        .unwrap();
+    }
+}
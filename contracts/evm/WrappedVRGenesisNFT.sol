@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.9;
+
+import "@openzeppelin/contracts/token/ERC721/extensions/ERC721URIStorage.sol";
+import "@openzeppelin/contracts/access/Ownable.sol";
+
+/**
+ * @title WrappedVRGenesisNFT
+ * @dev ERC721 on Moonbeam representing VR Genesis Frame tokens locked in the
+ * ink! collection, which stays the reserve. The bridge relayer (owner) mints
+ * a wrapped token for every `EvmWrapRequested` event, using the ink! token ID
+ * and the JSON from `erc721_metadata_json` as its URI, then reports the ID back with
+ * `confirm_evm_wrap`. Holders burn with `unwrap` to get the original back.
+ */
+contract WrappedVRGenesisNFT is ERC721URIStorage, Ownable {
+    // Events
+    event Wrapped(address indexed to, uint256 indexed tokenId);
+    event Unwrapped(address indexed from, uint256 indexed tokenId, bytes32 polkadotRecipient);
+
+    // Constructor
+    constructor() ERC721("Wrapped VR Genesis Frame", "wVRGF") {}
+
+    /**
+     * @dev Mint the wrapped token of a token locked on the ink! side
+     * @param to Address given as `evm_recipient` when the token was locked
+     * @param tokenId ID of the locked token in the ink! collection
+     * @param metadataURI Metadata for the token, e.g. a data URI of `erc721_metadata_json`
+     */
+    function wrap(address to, uint256 tokenId, string memory metadataURI)
+        public
+        onlyOwner
+    {
+        _mint(to, tokenId);
+        _setTokenURI(tokenId, metadataURI);
+
+        emit Wrapped(to, tokenId);
+    }
+
+    /**
+     * @dev Burn a wrapped token so the relayer releases the original on the
+     * ink! side with `unwrap_from_evm`
+     * @param tokenId Wrapped token ID
+     * @param polkadotRecipient Account ID the original is released to
+     */
+    function unwrap(uint256 tokenId, bytes32 polkadotRecipient)
+        public
+    {
+        require(
+            _isApprovedOrOwner(msg.sender, tokenId),
+            "WrappedVRGenesisNFT: Caller is not owner or approved"
+        );
+        require(polkadotRecipient != bytes32(0), "WrappedVRGenesisNFT: Zero recipient");
+
+        _burn(tokenId);
+
+        emit Unwrapped(msg.sender, tokenId, polkadotRecipient);
+    }
+}
@@ -0,0 +1,673 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::indexing_slicing
+    )
+)]
+
+/// VR Genesis Frame - Fractionalization Vault
+/// Shared ownership of a single high-value NFT, such as a genesis parcel.
+/// The curator deploys a vault for the token, approves it on the collection
+/// and deposits it, receiving a fixed supply of shares. The vault is itself
+/// the PSP22 share token, so shares trade like any other fungible token.
+/// Anyone can buy the NFT out with a native bid at or above the reserve
+/// price, which opens an auction; higher bids take over until it ends, and
+/// outbid bidders withdraw their refunds. Once settled, the NFT goes to the
+/// winner and shareholders burn their shares for a pro-rata cut of the
+/// winning bid. Holding every share, anyone can instead burn them all and
+/// take the NFT while no auction is running.
+#[ink::contract]
+pub mod fractional_vault {
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use polka_space_nft_core::{royalty_amount, PSP22Error, PSP22};
+    use scale::{Decode, Encode};
+
+    /// Minimum raise over the leading bid, in basis points
+    pub const MIN_BID_INCREMENT_BPS: u16 = 500;
+
+    /// Where the vault is in its life
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum VaultState {
+        /// Waiting for the curator to deposit the NFT
+        Pending,
+        /// Holding the NFT, with no bid yet
+        Active,
+        /// A buyout auction is running
+        Auction,
+        /// The NFT went to the auction winner; shares redeem for the proceeds
+        Sold,
+        /// Someone burned every share and took the NFT
+        Redeemed,
+    }
+
+    /// The leading bid of a buyout auction
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Bid {
+        pub bidder: AccountId,
+        pub amount: Balance,
+    }
+
+    /// Event emitted when the curator deposits the NFT and receives the shares
+    #[ink(event)]
+    pub struct Deposited {
+        #[ink(topic)]
+        curator: AccountId,
+        supply: Balance,
+    }
+
+    /// Event emitted when shares are minted, transferred or burned
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        value: Balance,
+    }
+
+    /// Event emitted when an owner sets a spender's share allowance
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        value: Balance,
+    }
+
+    /// Event emitted when a bid takes the lead, opening the auction if it's the first
+    #[ink(event)]
+    pub struct BidPlaced {
+        #[ink(topic)]
+        bidder: AccountId,
+        amount: Balance,
+        auction_end: BlockNumber,
+    }
+
+    /// Event emitted when an auction is settled and the NFT goes to the winner
+    #[ink(event)]
+    pub struct AuctionSettled {
+        #[ink(topic)]
+        winner: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when a shareholder burns shares for their cut of the proceeds
+    #[ink(event)]
+    pub struct SharesRedeemed {
+        #[ink(topic)]
+        holder: AccountId,
+        shares: Balance,
+        payout: Balance,
+    }
+
+    /// Event emitted when a holder of every share burns them for the NFT
+    #[ink(event)]
+    pub struct NftRedeemed {
+        #[ink(topic)]
+        holder: AccountId,
+    }
+
+    /// Errors returned by the vault
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Caller is not the curator
+        NotCurator,
+        /// The vault is not in the state the call needs
+        WrongState,
+        /// Share supply or auction length is zero
+        InvalidConfig,
+        /// Moving the NFT in the collection failed
+        CollectionCallFailed,
+        /// Bid is under the reserve price or the minimum raise
+        BidTooLow,
+        /// The auction has ended
+        AuctionClosed,
+        /// The auction is still running
+        AuctionRunning,
+        /// Caller holds fewer shares than needed
+        InsufficientShares,
+        /// Caller has no refund to withdraw
+        NothingToWithdraw,
+        /// Paying out native tokens failed
+        TransferFailed,
+        /// A counter or balance would overflow
+        Overflow,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    pub struct FractionalVault {
+        /// Account that deposits the NFT and receives the shares
+        curator: AccountId,
+        /// Collection of the vaulted NFT
+        collection: AccountId,
+        token_id: u32,
+        state: VaultState,
+        /// Smallest opening bid of a buyout auction
+        reserve_price: Balance,
+        /// Blocks an auction runs from its first bid
+        auction_length: BlockNumber,
+        /// Block the running auction ends at
+        auction_end: BlockNumber,
+        leading_bid: Option<Bid>,
+        /// Winning bid still owed to shareholders
+        proceeds: Balance,
+        /// Outbid amounts waiting to be withdrawn
+        refunds: Mapping<AccountId, Balance>,
+        /// Shares the curator receives on deposit
+        share_supply: Balance,
+        total_supply: Balance,
+        balances: Mapping<AccountId, Balance>,
+        allowances: Mapping<(AccountId, AccountId), Balance>,
+    }
+
+    impl FractionalVault {
+        /// Create a vault for `token_id` of `collection`, curated by the
+        /// caller, who receives `share_supply` shares on deposit
+        #[ink(constructor)]
+        pub fn new(
+            collection: AccountId,
+            token_id: u32,
+            share_supply: Balance,
+            reserve_price: Balance,
+            auction_length: BlockNumber,
+        ) -> Result<Self> {
+            if share_supply == 0 || auction_length == 0 {
+                return Err(Error::InvalidConfig);
+            }
+            Ok(Self {
+                curator: Self::env().caller(),
+                collection,
+                token_id,
+                state: VaultState::Pending,
+                reserve_price,
+                auction_length,
+                auction_end: 0,
+                leading_bid: None,
+                proceeds: 0,
+                refunds: Mapping::default(),
+                share_supply,
+                total_supply: 0,
+                balances: Mapping::default(),
+                allowances: Mapping::default(),
+            })
+        }
+
+        /// Get the curator account
+        #[ink(message)]
+        pub fn curator(&self) -> AccountId {
+            self.curator
+        }
+
+        /// Get the vaulted NFT's collection and token ID
+        #[ink(message)]
+        pub fn nft(&self) -> (AccountId, u32) {
+            (self.collection, self.token_id)
+        }
+
+        /// Get the vault's state
+        #[ink(message)]
+        pub fn state(&self) -> VaultState {
+            self.state
+        }
+
+        /// Get the smallest opening bid
+        #[ink(message)]
+        pub fn reserve_price(&self) -> Balance {
+            self.reserve_price
+        }
+
+        /// Get the leading bid and the block the auction ends at
+        #[ink(message)]
+        pub fn auction(&self) -> Option<(Bid, BlockNumber)> {
+            self.leading_bid.map(|bid| (bid, self.auction_end))
+        }
+
+        /// Get the smallest bid that would take the lead now
+        #[ink(message)]
+        pub fn min_bid(&self) -> Balance {
+            match self.leading_bid {
+                Some(bid) => bid
+                    .amount
+                    .saturating_add(royalty_amount(bid.amount, MIN_BID_INCREMENT_BPS).max(1)),
+                None => self.reserve_price,
+            }
+        }
+
+        /// Get the winning bid still owed to shareholders
+        #[ink(message)]
+        pub fn proceeds(&self) -> Balance {
+            self.proceeds
+        }
+
+        /// Get an outbid account's refund
+        #[ink(message)]
+        pub fn refund_of(&self, account: AccountId) -> Balance {
+            self.refunds.get(account).unwrap_or(0)
+        }
+
+        /// Get the shares in existence
+        #[ink(message)]
+        pub fn total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        /// Get the shares an account holds
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(owner).unwrap_or(0)
+        }
+
+        /// Get the shares `spender` may move for `owner`
+        #[ink(message)]
+        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get((owner, spender)).unwrap_or(0)
+        }
+
+        /// Let `spender` move up to `value` of the caller's shares
+        #[ink(message)]
+        pub fn approve(
+            &mut self,
+            spender: AccountId,
+            value: Balance,
+        ) -> core::result::Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            if spender == AccountId::from([0u8; 32]) {
+                return Err(PSP22Error::ZeroRecipientAddress);
+            }
+            self.allowances.insert((owner, spender), &value);
+            self.env().emit_event(Approval { owner, spender, value });
+            Ok(())
+        }
+
+        /// Move the NFT into the vault and mint the shares to the curator
+        /// (curator only, after approving the vault on the collection)
+        #[ink(message)]
+        pub fn deposit(&mut self) -> Result<()> {
+            if self.env().caller() != self.curator {
+                return Err(Error::NotCurator);
+            }
+            if self.state != VaultState::Pending {
+                return Err(Error::WrongState);
+            }
+            self.move_nft(self.env().account_id())?;
+            self.open_vault()
+        }
+
+        /// Bid the value sent for the NFT. The first bid must reach the
+        /// reserve price and opens the auction; later ones must beat the
+        /// leading bid by `MIN_BID_INCREMENT_BPS`, refunding it.
+        #[ink(message, payable)]
+        pub fn bid(&mut self) -> Result<()> {
+            let bidder = self.env().caller();
+            let amount = self.env().transferred_value();
+            let now = self.env().block_number();
+            match self.state {
+                VaultState::Active => {
+                    self.auction_end = now.checked_add(self.auction_length).ok_or(Error::Overflow)?;
+                }
+                VaultState::Auction if now >= self.auction_end => return Err(Error::AuctionClosed),
+                VaultState::Auction => {}
+                _ => return Err(Error::WrongState),
+            }
+            if amount < self.min_bid() {
+                return Err(Error::BidTooLow);
+            }
+
+            if let Some(outbid) = self.leading_bid {
+                let refund = self
+                    .refund_of(outbid.bidder)
+                    .checked_add(outbid.amount)
+                    .ok_or(Error::Overflow)?;
+                self.refunds.insert(outbid.bidder, &refund);
+            }
+            self.leading_bid = Some(Bid { bidder, amount });
+            self.state = VaultState::Auction;
+
+            self.env().emit_event(BidPlaced {
+                bidder,
+                amount,
+                auction_end: self.auction_end,
+            });
+            Ok(())
+        }
+
+        /// Send the NFT to the winner of an ended auction; anyone can call this
+        #[ink(message)]
+        pub fn settle(&mut self) -> Result<()> {
+            let winner = self.close_auction()?;
+            self.move_nft(winner.bidder)
+        }
+
+        /// Withdraw the caller's outbid amounts
+        #[ink(message)]
+        pub fn withdraw_refund(&mut self) -> Result<Balance> {
+            let caller = self.env().caller();
+            let amount = self.refund_of(caller);
+            if amount == 0 {
+                return Err(Error::NothingToWithdraw);
+            }
+            self.refunds.remove(caller);
+            self.pay_out(caller, amount)?;
+            Ok(amount)
+        }
+
+        /// Burn all the caller's shares for their cut of the winning bid
+        #[ink(message)]
+        pub fn redeem_shares(&mut self) -> Result<Balance> {
+            if self.state != VaultState::Sold {
+                return Err(Error::WrongState);
+            }
+            let holder = self.env().caller();
+            let shares = self.balance_of(holder);
+            if shares == 0 {
+                return Err(Error::InsufficientShares);
+            }
+
+            // The last shares out take whatever rounding left behind
+            let payout = if shares == self.total_supply {
+                self.proceeds
+            } else {
+                self.proceeds
+                    .checked_mul(shares)
+                    .and_then(|owed| owed.checked_div(self.total_supply))
+                    .ok_or(Error::Overflow)?
+            };
+            self.burn(holder, shares)?;
+            self.proceeds = self.proceeds.saturating_sub(payout);
+            self.pay_out(holder, payout)?;
+
+            self.env().emit_event(SharesRedeemed { holder, shares, payout });
+            Ok(payout)
+        }
+
+        /// Burn every share and take the NFT (holder of all shares only,
+        /// while no auction is running)
+        #[ink(message)]
+        pub fn redeem_nft(&mut self) -> Result<()> {
+            let holder = self.env().caller();
+            self.take_all_shares(holder)?;
+            self.move_nft(holder)
+        }
+
+        /// Mark the NFT as deposited and mint the shares to the curator
+        fn open_vault(&mut self) -> Result<()> {
+            let curator = self.curator;
+            self.state = VaultState::Active;
+            self.mint(curator, self.share_supply)?;
+
+            self.env().emit_event(Deposited {
+                curator,
+                supply: self.share_supply,
+            });
+            Ok(())
+        }
+
+        /// End an auction whose time is up, keeping the winning bid for shareholders
+        fn close_auction(&mut self) -> Result<Bid> {
+            if self.state != VaultState::Auction {
+                return Err(Error::WrongState);
+            }
+            if self.env().block_number() < self.auction_end {
+                return Err(Error::AuctionRunning);
+            }
+            let winner = self.leading_bid.ok_or(Error::WrongState)?;
+            self.state = VaultState::Sold;
+            self.proceeds = winner.amount;
+
+            self.env().emit_event(AuctionSettled {
+                winner: winner.bidder,
+                amount: winner.amount,
+            });
+            Ok(winner)
+        }
+
+        /// Burn every share from their sole holder, retiring the vault
+        fn take_all_shares(&mut self, holder: AccountId) -> Result<()> {
+            if self.state != VaultState::Active {
+                return Err(Error::WrongState);
+            }
+            if self.balance_of(holder) != self.total_supply {
+                return Err(Error::InsufficientShares);
+            }
+            self.burn(holder, self.total_supply)?;
+            self.state = VaultState::Redeemed;
+
+            self.env().emit_event(NftRedeemed { holder });
+            Ok(())
+        }
+
+        /// Move the vaulted NFT to `to` through the collection's `transfer`
+        /// message, as its owner or approved operator
+        fn move_nft(&self, to: AccountId) -> Result<()> {
+            let result = build_call::<Environment>()
+                .call(self.collection)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("transfer")))
+                        .push_arg(to)
+                        .push_arg(self.token_id),
+                )
+                // Collections' error enums differ; only the variant index is read
+                .returns::<core::result::Result<(), u8>>()
+                .try_invoke();
+            if !matches!(result, Ok(Ok(Ok(())))) {
+                return Err(Error::CollectionCallFailed);
+            }
+            Ok(())
+        }
+
+        /// Create shares for `to`
+        fn mint(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            self.total_supply = self.total_supply.checked_add(value).ok_or(Error::Overflow)?;
+            let balance = self.balance_of(to).checked_add(value).ok_or(Error::Overflow)?;
+            self.balances.insert(to, &balance);
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(to),
+                value,
+            });
+            Ok(())
+        }
+
+        /// Destroy shares of `from`
+        fn burn(&mut self, from: AccountId, value: Balance) -> Result<()> {
+            let balance = self.balance_of(from).checked_sub(value).ok_or(Error::InsufficientShares)?;
+            self.balances.insert(from, &balance);
+            self.total_supply = self.total_supply.saturating_sub(value);
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: None,
+                value,
+            });
+            Ok(())
+        }
+
+        /// Move shares between accounts
+        fn move_shares(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> core::result::Result<(), PSP22Error> {
+            if to == AccountId::from([0u8; 32]) {
+                return Err(PSP22Error::ZeroRecipientAddress);
+            }
+            let from_balance = self
+                .balance_of(from)
+                .checked_sub(value)
+                .ok_or(PSP22Error::InsufficientBalance)?;
+            self.balances.insert(from, &from_balance);
+            let to_balance = self.balance_of(to).saturating_add(value);
+            self.balances.insert(to, &to_balance);
+
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                value,
+            });
+            Ok(())
+        }
+
+        /// Pay out native tokens; state is updated before the transfer
+        fn pay_out(&mut self, to: AccountId, amount: Balance) -> Result<()> {
+            if amount == 0 {
+                return Ok(());
+            }
+            if self.env().transfer(to, amount).is_err() {
+                return Err(Error::TransferFailed);
+            }
+            Ok(())
+        }
+    }
+
+    impl PSP22 for FractionalVault {
+        /// Send `value` of the caller's shares to `to`
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> core::result::Result<(), PSP22Error> {
+            let from = self.env().caller();
+            self.move_shares(from, to, value)
+        }
+
+        /// Send `value` of `from`'s shares to `to`, spending the caller's allowance
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> core::result::Result<(), PSP22Error> {
+            let spender = self.env().caller();
+            if spender != from {
+                let allowance = self
+                    .allowance(from, spender)
+                    .checked_sub(value)
+                    .ok_or(PSP22Error::InsufficientAllowance)?;
+                self.allowances.insert((from, spender), &allowance);
+            }
+            self.move_shares(from, to, value)
+        }
+    }
+
+    /// Unit tests. The off-chain environment can't call the collection, so
+    /// these drive the helpers around the NFT moves directly.
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        fn new_vault() -> FractionalVault {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            test::set_callee::<DefaultEnvironment>(accounts.django);
+            let mut vault = FractionalVault::new(accounts.frank, 1, 1_000, 100, 10).unwrap();
+            vault.open_vault().unwrap();
+            vault
+        }
+
+        fn bid_as(vault: &mut FractionalVault, bidder: AccountId, amount: Balance) -> Result<()> {
+            test::set_caller::<DefaultEnvironment>(bidder);
+            test::set_value_transferred::<DefaultEnvironment>(amount);
+            vault.bid()
+        }
+
+        fn advance_blocks(count: u32) {
+            for _ in 0..count {
+                test::advance_block::<DefaultEnvironment>();
+            }
+        }
+
+        #[ink::test]
+        fn shares_trade_as_psp22() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            assert!(FractionalVault::new(accounts.frank, 1, 0, 100, 10).is_err());
+            let mut vault = new_vault();
+            assert_eq!(vault.state(), VaultState::Active);
+            assert_eq!(vault.total_supply(), 1_000);
+            assert_eq!(vault.balance_of(accounts.alice), 1_000);
+            assert_eq!(vault.deposit(), Err(Error::WrongState));
+
+            assert_eq!(PSP22::transfer(&mut vault, accounts.bob, 400, Vec::new()), Ok(()));
+            assert_eq!(vault.approve(accounts.charlie, 100), Ok(()));
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(
+                PSP22::transfer_from(&mut vault, accounts.alice, accounts.charlie, 150, Vec::new()),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+            assert_eq!(
+                PSP22::transfer_from(&mut vault, accounts.alice, accounts.charlie, 100, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(vault.balance_of(accounts.alice), 500);
+            assert_eq!(vault.balance_of(accounts.charlie), 100);
+
+            // Only a holder of every share can take the NFT
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(vault.take_all_shares(accounts.bob), Err(Error::InsufficientShares));
+            for holder in [accounts.alice, accounts.charlie] {
+                test::set_caller::<DefaultEnvironment>(holder);
+                let shares = vault.balance_of(holder);
+                PSP22::transfer(&mut vault, accounts.bob, shares, Vec::new()).unwrap();
+            }
+            assert_eq!(vault.take_all_shares(accounts.bob), Ok(()));
+            assert_eq!(vault.total_supply(), 0);
+            assert_eq!(vault.state(), VaultState::Redeemed);
+        }
+
+        #[ink::test]
+        fn buyout_auction_pays_shareholders_pro_rata() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut vault = new_vault();
+            // The off-chain environment doesn't credit bids to the vault
+            test::set_account_balance::<DefaultEnvironment>(accounts.django, 10_000);
+            PSP22::transfer(&mut vault, accounts.bob, 300, Vec::new()).unwrap();
+
+            assert_eq!(bid_as(&mut vault, accounts.charlie, 99), Err(Error::BidTooLow));
+            assert_eq!(bid_as(&mut vault, accounts.charlie, 100), Ok(()));
+            assert_eq!(vault.state(), VaultState::Auction);
+            assert_eq!(vault.min_bid(), 105);
+            assert_eq!(bid_as(&mut vault, accounts.eve, 104), Err(Error::BidTooLow));
+            assert_eq!(bid_as(&mut vault, accounts.eve, 1_000), Ok(()));
+            assert_eq!(vault.refund_of(accounts.charlie), 100);
+            assert_eq!(vault.close_auction(), Err(Error::AuctionRunning));
+            assert_eq!(vault.take_all_shares(accounts.alice), Err(Error::WrongState));
+
+            advance_blocks(10);
+            assert_eq!(bid_as(&mut vault, accounts.charlie, 2_000), Err(Error::AuctionClosed));
+            assert_eq!(vault.close_auction().map(|bid| bid.bidder), Ok(accounts.eve));
+            assert_eq!(vault.state(), VaultState::Sold);
+            assert_eq!(vault.proceeds(), 1_000);
+
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            let balance = test::get_account_balance::<DefaultEnvironment>(accounts.charlie).unwrap();
+            assert_eq!(vault.withdraw_refund(), Ok(100));
+            assert_eq!(vault.withdraw_refund(), Err(Error::NothingToWithdraw));
+            let refunded = test::get_account_balance::<DefaultEnvironment>(accounts.charlie).unwrap();
+            assert_eq!(refunded, balance + 100);
+            assert_eq!(vault.redeem_shares(), Err(Error::InsufficientShares));
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(vault.redeem_shares(), Ok(300));
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(vault.redeem_shares(), Ok(700));
+            assert_eq!(vault.total_supply(), 0);
+            assert_eq!(vault.proceeds(), 0);
+        }
+    }
+}
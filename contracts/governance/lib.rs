@@ -0,0 +1,1098 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::indexing_slicing
+    )
+)]
+
+/// VR Genesis Frame - Holder Governance
+/// Holders of a collection (VR land parcels, frames) propose and vote,
+/// weighted by their voting power just before the proposal was made, per the
+/// collection's `Votes`: their own tokens plus those delegated to them. A
+/// proposal carries the hash of its text and optionally a call this contract
+/// makes once it passes, so a collection administered by this contract is run
+/// by its holders. Proposals can instead be voted on quadratically, with votes
+/// bought against a native deposit that is refunded once voting ends. Passed
+/// proposals wait out a timelock during which a guardian, such as a team
+/// multisig, can veto them, until the guardian's sunset block. The contract
+/// keeps a treasury that only passed proposals spend from, calling back into
+/// `spend`, and no faster than a per-period cap for each currency. On
+/// critical proposals holders can also burn or lock a token in the collection
+/// for a vote worth many tokens, the sacrifice being recorded here. Proposals
+/// and their results are kept for good, so clients can page through the full
+/// governance history.
+#[ink::contract]
+pub mod governance {
+    use ink::codegen::TraitCallBuilder;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::CallFlags;
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use polka_space_nft_core::{Currency, SacrificeReceiver, Votes, PSP22};
+    use scale::{Decode, Encode, Output};
+
+    /// Maximum number of proposals returned by `proposals_paginated`
+    pub const MAX_PAGE_SIZE: u32 = 100;
+
+    /// A call made by the governance contract when a proposal passes
+    #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct ProposalCall {
+        pub callee: AccountId,
+        pub selector: [u8; 4],
+        /// SCALE-encoded message arguments
+        pub input: Vec<u8>,
+        pub transferred_value: Balance,
+        pub gas_limit: u64,
+    }
+
+    /// A proposal and its tally
+    #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Proposal {
+        pub proposer: AccountId,
+        /// Hash of the proposal text, published off-chain
+        pub description_hash: Hash,
+        pub call: Option<ProposalCall>,
+        /// Block the proposal was made in
+        pub start: BlockNumber,
+        /// Block whose closing holdings weigh the votes, the one before `start`,
+        /// so tokens bought once the proposal is known don't count
+        pub snapshot: BlockNumber,
+        /// Last block votes are accepted in
+        pub end: BlockNumber,
+        /// Last block of the timelock after voting, during which the guardian
+        /// may veto the proposal if it passed
+        pub timelock_end: BlockNumber,
+        /// Votes that must be cast, for and against, for the result to count
+        pub quorum: u32,
+        pub mode: VotingMode,
+        /// Whether holders may sacrifice tokens for amplified votes
+        pub critical: bool,
+        pub votes_for: u32,
+        pub votes_against: u32,
+        /// Block the proposal was executed in
+        pub executed_at: Option<BlockNumber>,
+        pub vetoed: bool,
+    }
+
+    /// How votes on a proposal are weighed
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum VotingMode {
+        /// One vote per unit of voting power
+        Linear,
+        /// Voters cast up to one vote per unit of voting power, escrowing
+        /// `votes² × vote_price` until voting ends
+        Quadratic { vote_price: Balance },
+    }
+
+    /// A holder's vote on a proposal
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Vote {
+        pub support: bool,
+        pub weight: u32,
+        /// Native balance held for a quadratic vote and not yet refunded
+        pub escrow: Balance,
+    }
+
+    /// A token burned or locked in the collection to vote on a critical proposal
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Sacrifice {
+        pub holder: AccountId,
+        pub support: bool,
+        /// Whether the token was burned rather than locked
+        pub burned: bool,
+        pub weight: u32,
+    }
+
+    /// A proposal with its ID and where it stands, for history pages
+    #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct ProposalRecord {
+        pub proposal_id: u32,
+        pub proposal: Proposal,
+        pub state: ProposalState,
+    }
+
+    /// Where a proposal stands
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum ProposalState {
+        /// Votes are still accepted
+        Active,
+        /// Voting ended without quorum or a majority for
+        Defeated,
+        /// Passed and waiting out the timelock; the guardian may veto it
+        Queued,
+        /// Passed and out of the timelock; can be executed
+        Succeeded,
+        Executed,
+        /// Vetoed by the guardian during the timelock
+        Vetoed,
+    }
+
+    /// Parameters proposals are made and decided with
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct GovernanceConfig {
+        /// Voting power an account needs to make a proposal
+        pub proposal_threshold: u32,
+        /// Votes that must be cast on a proposal, for and against
+        pub quorum: u32,
+        /// Blocks a proposal is open for voting
+        pub voting_period: BlockNumber,
+        /// Blocks a passed proposal waits before it can be executed
+        pub timelock: BlockNumber,
+        /// Length of the periods treasury spend caps apply to, in blocks
+        pub spend_period: BlockNumber,
+        /// Votes a token burned on a critical proposal is worth
+        pub burn_weight: u32,
+        /// Votes a token locked on a critical proposal is worth
+        pub lock_weight: u32,
+    }
+
+    /// Treasury spending in one currency during a spend period
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Spending {
+        /// Block number divided by `spend_period`
+        pub period: BlockNumber,
+        pub spent: Balance,
+    }
+
+    /// Account allowed to veto passed proposals during their timelock
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Guardian {
+        pub account: AccountId,
+        /// Last block the guardian may veto in
+        pub sunset: BlockNumber,
+    }
+
+    /// Event emitted when a proposal is made
+    #[ink(event)]
+    pub struct ProposalCreated {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        proposer: AccountId,
+        description_hash: Hash,
+        end: BlockNumber,
+    }
+
+    /// Event emitted when a holder votes
+    #[ink(event)]
+    pub struct VoteCast {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        voter: AccountId,
+        support: bool,
+        weight: u32,
+    }
+
+    /// Event emitted when a quadratic vote's deposit is returned
+    #[ink(event)]
+    pub struct EscrowRefunded {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        voter: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when the guardian vetoes a passed proposal
+    #[ink(event)]
+    pub struct ProposalVetoed {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        guardian: AccountId,
+    }
+
+    /// Event emitted when a holder burns or locks a token to vote on a critical proposal
+    #[ink(event)]
+    pub struct TokenSacrificed {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        holder: AccountId,
+        token_id: u32,
+        support: bool,
+        burned: bool,
+        weight: u32,
+    }
+
+    /// Event emitted when a passed proposal pays out of the treasury
+    #[ink(event)]
+    pub struct TreasurySpent {
+        #[ink(topic)]
+        to: AccountId,
+        currency: Currency,
+        amount: Balance,
+    }
+
+    /// Event emitted when a passed proposal is executed
+    #[ink(event)]
+    pub struct ProposalExecuted {
+        #[ink(topic)]
+        proposal_id: u32,
+    }
+
+    /// Errors returned by the governance contract
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Caller has less voting power than the proposal threshold
+        BelowThreshold,
+        /// Proposal does not exist
+        ProposalNotFound,
+        /// Voting on the proposal has ended
+        VotingClosed,
+        /// Caller already voted on the proposal
+        AlreadyVoted,
+        /// Caller had no voting power at the proposal's snapshot
+        NoVotingPower,
+        /// Proposal hasn't passed, is timelocked, or was already executed or vetoed
+        NotSucceeded,
+        /// The proposal's call failed
+        ExecutionFailed,
+        /// Only a passed proposal may do this
+        NotGovernance,
+        /// Voting period, spend period or quadratic vote price is zero
+        InvalidConfig,
+        /// A counter or tally would overflow
+        Overflow,
+        /// The proposal is voted on in the other mode
+        WrongVotingMode,
+        /// More quadratic votes than the voter's voting power
+        TooManyVotes,
+        /// The value sent doesn't match the price of the votes
+        WrongPayment,
+        /// Voting on the proposal is still open
+        VotingOpen,
+        /// No deposit held for the caller's vote
+        NoEscrow,
+        /// Returning a deposit or paying out of the treasury failed
+        TransferFailed,
+        /// Caller is not the guardian, or the guardian's sunset has passed
+        NotGuardian,
+        /// Proposal is not in its timelock
+        NotQueued,
+        /// The spend would take the period's spending past its cap
+        SpendCapExceeded,
+        /// The treasury holds less than the spend, not counting vote deposits
+        InsufficientFunds,
+        /// Tokens can only be sacrificed on critical proposals
+        NotCritical,
+        /// The token was already sacrificed on the proposal
+        AlreadySacrificed,
+        /// Caller is not the collection whose holders vote
+        NotToken,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    pub struct Governance {
+        /// Collection whose holders vote
+        token: AccountId,
+        config: GovernanceConfig,
+        proposals: Mapping<u32, Proposal>,
+        proposal_count: u32,
+        /// Votes cast, keyed by (proposal, voter)
+        votes: Mapping<(u32, AccountId), Vote>,
+        guardian: Option<Guardian>,
+        /// Most the treasury may spend per period in each currency
+        spend_caps: Mapping<Currency, Balance>,
+        /// Treasury spending in the latest period each currency was spent in
+        spending: Mapping<Currency, Spending>,
+        /// Quadratic vote deposits held, which the treasury can't spend
+        total_escrow: Balance,
+        /// Tokens sacrificed, keyed by (proposal, token)
+        sacrifices: Mapping<(u32, u32), Sacrifice>,
+    }
+
+    impl Governance {
+        /// Create a governance contract for the holders of `token`, with an
+        /// optional guardian for its early days
+        #[ink(constructor)]
+        pub fn new(token: AccountId, config: GovernanceConfig, guardian: Option<Guardian>) -> Result<Self> {
+            if config.voting_period == 0 || config.spend_period == 0 {
+                return Err(Error::InvalidConfig);
+            }
+            Ok(Self {
+                token,
+                config,
+                proposals: Mapping::default(),
+                proposal_count: 0,
+                votes: Mapping::default(),
+                guardian,
+                spend_caps: Mapping::default(),
+                spending: Mapping::default(),
+                total_escrow: 0,
+                sacrifices: Mapping::default(),
+            })
+        }
+
+        /// Get the collection whose holders vote
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            self.token
+        }
+
+        /// Get the parameters new proposals are made with
+        #[ink(message)]
+        pub fn config(&self) -> GovernanceConfig {
+            self.config
+        }
+
+        /// Get the guardian, which may veto passed proposals until its sunset block
+        #[ink(message)]
+        pub fn guardian(&self) -> Option<Guardian> {
+            self.guardian
+        }
+
+        /// Change the parameters for new proposals (passed proposals only)
+        #[ink(message)]
+        pub fn set_config(&mut self, config: GovernanceConfig) -> Result<()> {
+            if self.env().caller() != self.env().account_id() {
+                return Err(Error::NotGovernance);
+            }
+            if config.voting_period == 0 || config.spend_period == 0 {
+                return Err(Error::InvalidConfig);
+            }
+            self.config = config;
+            Ok(())
+        }
+
+        /// Get the most the treasury may spend per period in a currency
+        #[ink(message)]
+        pub fn spend_cap(&self, currency: Currency) -> Balance {
+            self.spend_caps.get(currency).unwrap_or(0)
+        }
+
+        /// Get how much the treasury has spent in a currency this period
+        #[ink(message)]
+        pub fn spent_this_period(&self, currency: Currency) -> Balance {
+            self.spending
+                .get(currency)
+                .filter(|spending| spending.period == self.current_period())
+                .map_or(0, |spending| spending.spent)
+        }
+
+        /// Change the treasury's per-period cap in a currency (passed proposals only)
+        #[ink(message)]
+        pub fn set_spend_cap(&mut self, currency: Currency, cap: Balance) -> Result<()> {
+            if self.env().caller() != self.env().account_id() {
+                return Err(Error::NotGovernance);
+            }
+            self.spend_caps.insert(currency, &cap);
+            Ok(())
+        }
+
+        /// Pay `amount` of a currency out of the treasury, within the
+        /// period's cap (passed proposals only)
+        #[ink(message)]
+        pub fn spend(&mut self, to: AccountId, amount: Balance, currency: Currency) -> Result<()> {
+            if self.env().caller() != self.env().account_id() {
+                return Err(Error::NotGovernance);
+            }
+            let spent = self
+                .spent_this_period(currency)
+                .checked_add(amount)
+                .ok_or(Error::Overflow)?;
+            if spent > self.spend_cap(currency) {
+                return Err(Error::SpendCapExceeded);
+            }
+            if currency == Currency::Native && self.env().balance().saturating_sub(self.total_escrow) < amount {
+                return Err(Error::InsufficientFunds);
+            }
+            self.spending.insert(
+                currency,
+                &Spending {
+                    period: self.current_period(),
+                    spent,
+                },
+            );
+
+            let paid = match currency {
+                Currency::Native => self.env().transfer(to, amount).is_ok(),
+                Currency::Psp22(token) => {
+                    let mut token: ink::contract_ref!(PSP22) = token.into();
+                    matches!(
+                        token.call_mut().transfer(to, amount, Vec::new()).try_invoke(),
+                        Ok(Ok(Ok(())))
+                    )
+                }
+                // This contract's environment has no assets chain extension
+                Currency::Asset(_) => false,
+            };
+            if !paid {
+                return Err(Error::TransferFailed);
+            }
+
+            self.env().emit_event(TreasurySpent { to, currency, amount });
+            Ok(())
+        }
+
+        /// Get the number of proposals made
+        #[ink(message)]
+        pub fn proposal_count(&self) -> u32 {
+            self.proposal_count
+        }
+
+        /// Get a proposal
+        #[ink(message)]
+        pub fn proposal(&self, proposal_id: u32) -> Option<Proposal> {
+            self.proposals.get(proposal_id)
+        }
+
+        /// Get proposals `start..start + limit` with where each stands, oldest
+        /// first, at most `MAX_PAGE_SIZE` at a time
+        #[ink(message)]
+        pub fn proposals_paginated(&self, start: u32, limit: u32) -> Vec<ProposalRecord> {
+            let end = start
+                .saturating_add(limit.min(MAX_PAGE_SIZE))
+                .min(self.proposal_count);
+            (start..end)
+                .filter_map(|proposal_id| {
+                    let proposal = self.proposals.get(proposal_id)?;
+                    let state = self.state_of(&proposal);
+                    Some(ProposalRecord { proposal_id, proposal, state })
+                })
+                .collect()
+        }
+
+        /// Get where a proposal stands
+        #[ink(message)]
+        pub fn state(&self, proposal_id: u32) -> Option<ProposalState> {
+            self.proposals.get(proposal_id).map(|proposal| self.state_of(&proposal))
+        }
+
+        /// Get an account's vote on a proposal
+        #[ink(message)]
+        pub fn vote_of(&self, proposal_id: u32, voter: AccountId) -> Option<Vote> {
+            self.votes.get((proposal_id, voter))
+        }
+
+        /// Get the sacrifice a token was used for on a proposal
+        #[ink(message)]
+        pub fn sacrifice_of(&self, proposal_id: u32, token_id: u32) -> Option<Sacrifice> {
+            self.sacrifices.get((proposal_id, token_id))
+        }
+
+        /// Make a proposal (accounts with at least `proposal_threshold` voting
+        /// power as of the previous block). `call` is made by this contract if
+        /// the proposal passes; on `critical` ones holders may sacrifice tokens.
+        #[ink(message)]
+        pub fn propose(
+            &mut self,
+            description_hash: Hash,
+            call: Option<ProposalCall>,
+            mode: VotingMode,
+            critical: bool,
+        ) -> Result<u32> {
+            let caller = self.env().caller();
+            let weight = self.voting_weight(caller, self.env().block_number().saturating_sub(1));
+            self.create_proposal(caller, weight, description_hash, call, mode, critical)
+        }
+
+        /// Vote for or against an active linear proposal, weighted by voting
+        /// power at its snapshot block
+        #[ink(message)]
+        pub fn vote(&mut self, proposal_id: u32, support: bool) -> Result<u32> {
+            let caller = self.env().caller();
+            let proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+            let weight = self.voting_weight(caller, proposal.snapshot);
+            self.cast_vote(caller, proposal_id, support, weight)
+        }
+
+        /// Cast `votes` for or against an active quadratic proposal, at most
+        /// the caller's voting power at its snapshot block. The value sent
+        /// must be `votes² × vote_price`; it is held until voting ends.
+        #[ink(message, payable)]
+        pub fn vote_quadratic(&mut self, proposal_id: u32, support: bool, votes: u32) -> Result<u32> {
+            let caller = self.env().caller();
+            let proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+            let power = self.voting_weight(caller, proposal.snapshot);
+            let paid = self.env().transferred_value();
+            self.cast_quadratic_vote(caller, proposal_id, support, votes, power, paid)
+        }
+
+        /// Return the caller's quadratic vote deposit once voting has ended
+        #[ink(message)]
+        pub fn refund_escrow(&mut self, proposal_id: u32) -> Result<Balance> {
+            let voter = self.env().caller();
+            let proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+            if self.state_of(&proposal) == ProposalState::Active {
+                return Err(Error::VotingOpen);
+            }
+            let mut vote = self
+                .votes
+                .get((proposal_id, voter))
+                .filter(|vote| vote.escrow > 0)
+                .ok_or(Error::NoEscrow)?;
+
+            let amount = vote.escrow;
+            vote.escrow = 0;
+            self.votes.insert((proposal_id, voter), &vote);
+            self.total_escrow = self.total_escrow.saturating_sub(amount);
+            if self.env().transfer(voter, amount).is_err() {
+                return Err(Error::TransferFailed);
+            }
+
+            self.env().emit_event(EscrowRefunded {
+                proposal_id,
+                voter,
+                amount,
+            });
+
+            Ok(amount)
+        }
+
+        /// Veto a passed proposal during its timelock (guardian only, until its sunset)
+        #[ink(message)]
+        pub fn veto(&mut self, proposal_id: u32) -> Result<()> {
+            let guardian = self.env().caller();
+            let now = self.env().block_number();
+            if !self.guardian.is_some_and(|g| g.account == guardian && now <= g.sunset) {
+                return Err(Error::NotGuardian);
+            }
+            let mut proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+            if self.state_of(&proposal) != ProposalState::Queued {
+                return Err(Error::NotQueued);
+            }
+
+            proposal.vetoed = true;
+            self.proposals.insert(proposal_id, &proposal);
+
+            self.env().emit_event(ProposalVetoed { proposal_id, guardian });
+            Ok(())
+        }
+
+        /// Execute a passed proposal once its timelock is over, making its
+        /// call if it has one. Anyone may call this.
+        #[ink(message)]
+        pub fn execute(&mut self, proposal_id: u32) -> Result<()> {
+            let mut proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+            if self.state_of(&proposal) != ProposalState::Succeeded {
+                return Err(Error::NotSucceeded);
+            }
+
+            // Marked first so the call can't execute it again
+            proposal.executed_at = Some(self.env().block_number());
+            self.proposals.insert(proposal_id, &proposal);
+            if let Some(call) = &proposal.call {
+                self.make_call(call)?;
+            }
+
+            self.env().emit_event(ProposalExecuted { proposal_id });
+            Ok(())
+        }
+
+        /// Get the voting power of `account` at the end of `block`;
+        /// failed calls count as none
+        fn voting_weight(&self, account: AccountId, block: BlockNumber) -> u32 {
+            let token: ink::contract_ref!(Votes) = self.token.into();
+            token
+                .call()
+                .votes_at(account, block)
+                .try_invoke()
+                .ok()
+                .and_then(|result| result.ok())
+                .unwrap_or(0)
+        }
+
+        /// Spend period the current block falls in
+        fn current_period(&self) -> BlockNumber {
+            self.env()
+                .block_number()
+                .checked_div(self.config.spend_period)
+                .unwrap_or(0)
+        }
+
+        /// Where a proposal stands at the current block
+        fn state_of(&self, proposal: &Proposal) -> ProposalState {
+            if proposal.executed_at.is_some() {
+                return ProposalState::Executed;
+            }
+            if proposal.vetoed {
+                return ProposalState::Vetoed;
+            }
+            let now = self.env().block_number();
+            if now <= proposal.end {
+                return ProposalState::Active;
+            }
+            let turnout = u64::from(proposal.votes_for).saturating_add(u64::from(proposal.votes_against));
+            if turnout < u64::from(proposal.quorum) || proposal.votes_for <= proposal.votes_against {
+                ProposalState::Defeated
+            } else if now <= proposal.timelock_end {
+                ProposalState::Queued
+            } else {
+                ProposalState::Succeeded
+            }
+        }
+
+        /// Record a proposal by a proposer with `weight` voting power
+        fn create_proposal(
+            &mut self,
+            proposer: AccountId,
+            weight: u32,
+            description_hash: Hash,
+            call: Option<ProposalCall>,
+            mode: VotingMode,
+            critical: bool,
+        ) -> Result<u32> {
+            if weight < self.config.proposal_threshold {
+                return Err(Error::BelowThreshold);
+            }
+            if mode == (VotingMode::Quadratic { vote_price: 0 }) {
+                return Err(Error::InvalidConfig);
+            }
+
+            let proposal_id = self.proposal_count;
+            self.proposal_count = proposal_id.checked_add(1).ok_or(Error::Overflow)?;
+            let start = self.env().block_number();
+            let end = start.saturating_add(self.config.voting_period);
+            self.proposals.insert(
+                proposal_id,
+                &Proposal {
+                    proposer,
+                    description_hash,
+                    call,
+                    start,
+                    snapshot: start.saturating_sub(1),
+                    end,
+                    timelock_end: end.saturating_add(self.config.timelock),
+                    quorum: self.config.quorum,
+                    mode,
+                    critical,
+                    votes_for: 0,
+                    votes_against: 0,
+                    executed_at: None,
+                    vetoed: false,
+                },
+            );
+
+            self.env().emit_event(ProposalCreated {
+                proposal_id,
+                proposer,
+                description_hash,
+                end,
+            });
+
+            Ok(proposal_id)
+        }
+
+        /// Record a linear vote by a voter with `weight` voting power
+        fn cast_vote(&mut self, voter: AccountId, proposal_id: u32, support: bool, weight: u32) -> Result<u32> {
+            let proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+            if proposal.mode != VotingMode::Linear {
+                return Err(Error::WrongVotingMode);
+            }
+            self.record_vote(voter, proposal, proposal_id, support, weight, 0)
+        }
+
+        /// Record a quadratic vote of `votes` by a voter with `power` voting
+        /// power who paid `paid`
+        fn cast_quadratic_vote(
+            &mut self,
+            voter: AccountId,
+            proposal_id: u32,
+            support: bool,
+            votes: u32,
+            power: u32,
+            paid: Balance,
+        ) -> Result<u32> {
+            let proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+            let vote_price = match proposal.mode {
+                VotingMode::Quadratic { vote_price } => vote_price,
+                VotingMode::Linear => return Err(Error::WrongVotingMode),
+            };
+            if votes > power {
+                return Err(Error::TooManyVotes);
+            }
+            let cost = Balance::from(votes)
+                .checked_mul(Balance::from(votes))
+                .and_then(|squared| squared.checked_mul(vote_price))
+                .ok_or(Error::Overflow)?;
+            if paid != cost {
+                return Err(Error::WrongPayment);
+            }
+            self.record_vote(voter, proposal, proposal_id, support, votes, cost)
+        }
+
+        /// Record a sacrifice reported by `collection` and add its weight to
+        /// the proposal's tally
+        fn record_sacrifice(
+            &mut self,
+            collection: AccountId,
+            holder: AccountId,
+            token_id: u32,
+            proposal_id: u32,
+            support: bool,
+            burned: bool,
+        ) -> Result<u32> {
+            if collection != self.token {
+                return Err(Error::NotToken);
+            }
+            let mut proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+            if !proposal.critical {
+                return Err(Error::NotCritical);
+            }
+            if self.state_of(&proposal) != ProposalState::Active {
+                return Err(Error::VotingClosed);
+            }
+            if self.sacrifices.contains((proposal_id, token_id)) {
+                return Err(Error::AlreadySacrificed);
+            }
+
+            let weight = if burned {
+                self.config.burn_weight
+            } else {
+                self.config.lock_weight
+            };
+            let tally = if support {
+                &mut proposal.votes_for
+            } else {
+                &mut proposal.votes_against
+            };
+            *tally = tally.checked_add(weight).ok_or(Error::Overflow)?;
+            self.proposals.insert(proposal_id, &proposal);
+            self.sacrifices.insert(
+                (proposal_id, token_id),
+                &Sacrifice {
+                    holder,
+                    support,
+                    burned,
+                    weight,
+                },
+            );
+
+            self.env().emit_event(TokenSacrificed {
+                proposal_id,
+                holder,
+                token_id,
+                support,
+                burned,
+                weight,
+            });
+
+            Ok(weight)
+        }
+
+        /// Add a vote to a proposal's tally, holding `escrow` for the voter
+        fn record_vote(
+            &mut self,
+            voter: AccountId,
+            mut proposal: Proposal,
+            proposal_id: u32,
+            support: bool,
+            weight: u32,
+            escrow: Balance,
+        ) -> Result<u32> {
+            if self.state_of(&proposal) != ProposalState::Active {
+                return Err(Error::VotingClosed);
+            }
+            if self.votes.contains((proposal_id, voter)) {
+                return Err(Error::AlreadyVoted);
+            }
+            if weight == 0 {
+                return Err(Error::NoVotingPower);
+            }
+
+            let tally = if support {
+                &mut proposal.votes_for
+            } else {
+                &mut proposal.votes_against
+            };
+            *tally = tally.checked_add(weight).ok_or(Error::Overflow)?;
+            self.proposals.insert(proposal_id, &proposal);
+            self.total_escrow = self.total_escrow.checked_add(escrow).ok_or(Error::Overflow)?;
+            self.votes.insert((proposal_id, voter), &Vote { support, weight, escrow });
+
+            self.env().emit_event(VoteCast {
+                proposal_id,
+                voter,
+                support,
+                weight,
+            });
+
+            Ok(weight)
+        }
+
+        /// Make a passed proposal's call. Calls back into this contract are
+        /// allowed, so proposals can change its config.
+        fn make_call(&self, call: &ProposalCall) -> Result<()> {
+            let flags = CallFlags::default().set_allow_reentry(call.callee == self.env().account_id());
+            let result = build_call::<Environment>()
+                .call(call.callee)
+                .gas_limit(call.gas_limit)
+                .transferred_value(call.transferred_value)
+                .call_flags(flags)
+                .exec_input(ExecutionInput::new(Selector::new(call.selector)).push_arg(RawInput(&call.input)))
+                .returns::<()>()
+                .try_invoke();
+            if !matches!(result, Ok(Ok(()))) {
+                return Err(Error::ExecutionFailed);
+            }
+            Ok(())
+        }
+    }
+
+    impl SacrificeReceiver for Governance {
+        /// Count a token the collection burned or locked as an amplified vote
+        #[ink(message)]
+        fn on_sacrifice(&mut self, holder: AccountId, token_id: u32, proposal_id: u32, support: bool, burned: bool) -> bool {
+            let collection = self.env().caller();
+            self.record_sacrifice(collection, holder, token_id, proposal_id, support, burned)
+                .is_ok()
+        }
+    }
+
+    /// Arguments that are already SCALE-encoded, written as they are
+    struct RawInput<'a>(&'a [u8]);
+
+    impl Encode for RawInput<'_> {
+        fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+            dest.write(self.0);
+        }
+    }
+
+    /// Unit tests. The off-chain environment can't call the collection, so
+    /// these drive the helpers behind the balance lookup directly.
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        const CONFIG: GovernanceConfig = GovernanceConfig {
+            proposal_threshold: 2,
+            quorum: 5,
+            voting_period: 3,
+            timelock: 0,
+            spend_period: 10,
+            burn_weight: 10,
+            lock_weight: 4,
+        };
+
+        fn new_governance() -> Governance {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            test::set_callee::<DefaultEnvironment>(accounts.django);
+            Governance::new(accounts.frank, CONFIG, None).unwrap()
+        }
+
+        fn advance_blocks(count: u32) {
+            for _ in 0..count {
+                test::advance_block::<DefaultEnvironment>();
+            }
+        }
+
+        #[ink::test]
+        fn proposals_pass_with_quorum_and_majority() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut governance = new_governance();
+            let text = Hash::from([7; 32]);
+
+            assert_eq!(governance.create_proposal(accounts.bob, 1, text, None, VotingMode::Linear, false), Err(Error::BelowThreshold));
+            let proposal_id = governance.create_proposal(accounts.bob, 2, text, None, VotingMode::Linear, false).unwrap();
+            assert_eq!(governance.state(proposal_id), Some(ProposalState::Active));
+            assert_eq!(governance.proposal(proposal_id).map(|proposal| proposal.snapshot), Some(0));
+
+            assert_eq!(governance.cast_vote(accounts.bob, proposal_id, true, 2), Ok(2));
+            assert_eq!(governance.cast_vote(accounts.bob, proposal_id, true, 2), Err(Error::AlreadyVoted));
+            assert_eq!(governance.cast_vote(accounts.eve, proposal_id, false, 0), Err(Error::NoVotingPower));
+            assert_eq!(governance.cast_vote(accounts.charlie, proposal_id, true, 3), Ok(3));
+            assert_eq!(governance.cast_vote(accounts.django, proposal_id, false, 4), Ok(4));
+            assert_eq!(governance.vote_of(proposal_id, accounts.django), Some(Vote { support: false, weight: 4, escrow: 0 }));
+            assert_eq!(governance.execute(proposal_id), Err(Error::NotSucceeded));
+
+            advance_blocks(4);
+            assert_eq!(governance.cast_vote(accounts.eve, proposal_id, false, 9), Err(Error::VotingClosed));
+            assert_eq!(governance.state(proposal_id), Some(ProposalState::Succeeded));
+            assert_eq!(governance.execute(proposal_id), Ok(()));
+            assert_eq!(governance.state(proposal_id), Some(ProposalState::Executed));
+            assert_eq!(governance.proposal(proposal_id).and_then(|proposal| proposal.executed_at), Some(4));
+            assert_eq!(governance.execute(proposal_id), Err(Error::NotSucceeded));
+        }
+
+        #[ink::test]
+        fn proposals_without_quorum_or_majority_fail() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut governance = new_governance();
+            let quiet = governance.create_proposal(accounts.bob, 9, Hash::from([1; 32]), None, VotingMode::Linear, false).unwrap();
+            let split = governance.create_proposal(accounts.bob, 9, Hash::from([2; 32]), None, VotingMode::Linear, false).unwrap();
+
+            governance.cast_vote(accounts.bob, quiet, true, 4).unwrap();
+            governance.cast_vote(accounts.bob, split, true, 3).unwrap();
+            governance.cast_vote(accounts.charlie, split, false, 3).unwrap();
+
+            advance_blocks(4);
+            assert_eq!(governance.state(quiet), Some(ProposalState::Defeated));
+            assert_eq!(governance.state(split), Some(ProposalState::Defeated));
+            assert_eq!(governance.execute(quiet), Err(Error::NotSucceeded));
+            assert_eq!(governance.state(2), None);
+
+            // Results stay queryable, page by page
+            let history = governance.proposals_paginated(1, 5);
+            assert_eq!(history.len(), 1);
+            assert_eq!((history[0].proposal_id, history[0].state), (split, ProposalState::Defeated));
+            assert_eq!((history[0].proposal.votes_for, history[0].proposal.votes_against), (3, 3));
+            assert!(governance.proposals_paginated(2, 5).is_empty());
+
+            // Only passed proposals, calling back in, change the config
+            assert_eq!(governance.set_config(CONFIG), Err(Error::NotGovernance));
+        }
+
+        #[ink::test]
+        fn quadratic_votes_cost_the_square_and_are_refunded() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut governance = new_governance();
+            let mode = VotingMode::Quadratic { vote_price: 10 };
+            assert_eq!(
+                governance.create_proposal(accounts.bob, 9, Hash::from([1; 32]), None, VotingMode::Quadratic { vote_price: 0 }, false),
+                Err(Error::InvalidConfig)
+            );
+            let proposal_id = governance.create_proposal(accounts.bob, 9, Hash::from([1; 32]), None, mode, false).unwrap();
+
+            assert_eq!(governance.cast_vote(accounts.bob, proposal_id, true, 9), Err(Error::WrongVotingMode));
+            assert_eq!(
+                governance.cast_quadratic_vote(accounts.bob, proposal_id, true, 10, 9, 1_000),
+                Err(Error::TooManyVotes)
+            );
+            assert_eq!(
+                governance.cast_quadratic_vote(accounts.bob, proposal_id, true, 3, 9, 30),
+                Err(Error::WrongPayment)
+            );
+            // Nine times the deposit buys only three times the votes
+            assert_eq!(governance.cast_quadratic_vote(accounts.bob, proposal_id, true, 3, 9, 90), Ok(3));
+            assert_eq!(governance.cast_quadratic_vote(accounts.charlie, proposal_id, false, 1, 1, 10), Ok(1));
+            test::set_account_balance::<DefaultEnvironment>(accounts.django, 100);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(governance.refund_escrow(proposal_id), Err(Error::VotingOpen));
+            advance_blocks(4);
+            assert_eq!(governance.refund_escrow(proposal_id), Ok(90));
+            assert_eq!(governance.refund_escrow(proposal_id), Err(Error::NoEscrow));
+            assert_eq!(governance.vote_of(proposal_id, accounts.bob).map(|vote| vote.weight), Some(3));
+            assert_eq!(test::get_account_balance::<DefaultEnvironment>(accounts.django), Ok(10));
+        }
+
+        #[ink::test]
+        fn guardian_vetoes_during_the_timelock_until_its_sunset() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            test::set_callee::<DefaultEnvironment>(accounts.django);
+            let config = GovernanceConfig { timelock: 2, ..CONFIG };
+            let guardian = Guardian {
+                account: accounts.eve,
+                sunset: 6,
+            };
+            let mut governance = Governance::new(accounts.frank, config, Some(guardian)).unwrap();
+            let text = Hash::from([1; 32]);
+            let vetoed = governance.create_proposal(accounts.bob, 9, text, None, VotingMode::Linear, false).unwrap();
+            let passed = governance.create_proposal(accounts.bob, 9, text, None, VotingMode::Linear, false).unwrap();
+            governance.cast_vote(accounts.bob, vetoed, true, 9).unwrap();
+            governance.cast_vote(accounts.bob, passed, true, 9).unwrap();
+
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(governance.veto(vetoed), Err(Error::NotQueued));
+            advance_blocks(4);
+            assert_eq!(governance.state(vetoed), Some(ProposalState::Queued));
+            assert_eq!(governance.execute(vetoed), Err(Error::NotSucceeded));
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(governance.veto(vetoed), Err(Error::NotGuardian));
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(governance.veto(vetoed), Ok(()));
+            assert_eq!(governance.state(vetoed), Some(ProposalState::Vetoed));
+
+            advance_blocks(2);
+            assert_eq!(governance.state(passed), Some(ProposalState::Succeeded));
+            assert_eq!(governance.veto(passed), Err(Error::NotQueued));
+            assert_eq!(governance.execute(vetoed), Err(Error::NotSucceeded));
+            assert_eq!(governance.execute(passed), Ok(()));
+
+            // Past its sunset the guardian can't veto anymore
+            let late = governance.create_proposal(accounts.bob, 9, text, None, VotingMode::Linear, false).unwrap();
+            governance.cast_vote(accounts.bob, late, true, 9).unwrap();
+            advance_blocks(4);
+            assert_eq!(governance.state(late), Some(ProposalState::Queued));
+            assert_eq!(governance.veto(late), Err(Error::NotGuardian));
+        }
+
+        #[ink::test]
+        fn treasury_spends_within_the_period_cap() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut governance = new_governance();
+            test::set_account_balance::<DefaultEnvironment>(accounts.django, 1_000);
+            assert_eq!(governance.spend(accounts.bob, 10, Currency::Native), Err(Error::NotGovernance));
+
+            // Passed proposals call back in as the contract itself
+            test::set_caller::<DefaultEnvironment>(accounts.django);
+            assert_eq!(governance.spend(accounts.bob, 10, Currency::Native), Err(Error::SpendCapExceeded));
+            governance.set_spend_cap(Currency::Native, 100).unwrap();
+            assert_eq!(governance.spend(accounts.bob, 60, Currency::Native), Ok(()));
+            assert_eq!(governance.spend(accounts.bob, 50, Currency::Native), Err(Error::SpendCapExceeded));
+            assert_eq!(governance.spent_this_period(Currency::Native), 60);
+            assert_eq!(governance.spend_cap(Currency::Psp22(accounts.eve)), 0);
+
+            advance_blocks(10);
+            assert_eq!(governance.spent_this_period(Currency::Native), 0);
+            assert_eq!(governance.spend(accounts.bob, 100, Currency::Native), Ok(()));
+            assert_eq!(test::get_account_balance::<DefaultEnvironment>(accounts.django), Ok(840));
+
+            // Quadratic vote deposits aren't the treasury's to spend
+            let mode = VotingMode::Quadratic { vote_price: 100 };
+            let proposal_id = governance.create_proposal(accounts.bob, 9, Hash::from([1; 32]), None, mode, false).unwrap();
+            governance.cast_quadratic_vote(accounts.bob, proposal_id, true, 2, 9, 400).unwrap();
+            advance_blocks(10);
+            governance.set_spend_cap(Currency::Native, 1_000).unwrap();
+            assert_eq!(governance.spend(accounts.bob, 500, Currency::Native), Err(Error::InsufficientFunds));
+            assert_eq!(governance.spend(accounts.bob, 440, Currency::Native), Ok(()));
+        }
+
+        #[ink::test]
+        fn sacrificed_tokens_cast_amplified_votes_on_critical_proposals() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut governance = new_governance();
+            let text = Hash::from([1; 32]);
+            let routine = governance.create_proposal(accounts.bob, 9, text, None, VotingMode::Linear, false).unwrap();
+            let reset = governance.create_proposal(accounts.bob, 9, text, None, VotingMode::Linear, true).unwrap();
+
+            // Only the collection reports sacrifices
+            assert_eq!(
+                governance.record_sacrifice(accounts.bob, accounts.bob, 1, reset, true, true),
+                Err(Error::NotToken)
+            );
+            assert_eq!(
+                governance.record_sacrifice(accounts.frank, accounts.bob, 1, routine, true, true),
+                Err(Error::NotCritical)
+            );
+            assert_eq!(governance.record_sacrifice(accounts.frank, accounts.bob, 1, reset, true, true), Ok(10));
+            assert_eq!(
+                governance.record_sacrifice(accounts.frank, accounts.bob, 1, reset, true, false),
+                Err(Error::AlreadySacrificed)
+            );
+            assert_eq!(governance.record_sacrifice(accounts.frank, accounts.charlie, 2, reset, false, false), Ok(4));
+            assert_eq!(governance.cast_vote(accounts.charlie, reset, false, 3), Ok(3));
+            assert_eq!(
+                governance.sacrifice_of(reset, 1),
+                Some(Sacrifice { holder: accounts.bob, support: true, burned: true, weight: 10 })
+            );
+
+            advance_blocks(4);
+            assert_eq!(governance.state(reset), Some(ProposalState::Succeeded));
+            assert_eq!(
+                governance.record_sacrifice(accounts.frank, accounts.bob, 3, reset, true, true),
+                Err(Error::VotingClosed)
+            );
+        }
+    }
+}
@@ -30,6 +30,14 @@ mod minimal_nft {
         token_id: u32,
     }
 
+    /// Errors returned by the collection
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        /// The token counter is exhausted
+        Overflow,
+    }
+
     impl Default for MinimalNft {
         fn default() -> Self {
             Self::new()
@@ -49,9 +57,9 @@ mod minimal_nft {
 
         /// Mint a new token
         #[ink(message)]
-        pub fn mint(&mut self, uri: String) -> u32 {
+        pub fn mint(&mut self, uri: String) -> Result<u32, Error> {
             let caller = self.env().caller();
-            let token_id = self.token_count.checked_add(1).unwrap_or(1);
+            let token_id = self.token_count.checked_add(1).ok_or(Error::Overflow)?;
             
             self.token_owner.insert(token_id, &caller);
             self.token_uri.insert(token_id, &uri);
@@ -63,7 +71,7 @@ mod minimal_nft {
                 token_id,
             });
             
-            token_id
+            Ok(token_id)
         }
 
         /// Get token owner
@@ -99,7 +107,7 @@ mod minimal_nft {
             let mut nft = MinimalNft::new();
             let token_uri = String::from("ipfs://test");
             
-            let token_id = nft.mint(token_uri.clone());
+            let token_id = nft.mint(token_uri.clone()).unwrap();
             assert_eq!(token_id, 1);
             assert_eq!(nft.total_supply(), 1);
             
@@ -109,5 +117,55 @@ mod minimal_nft {
             let uri = nft.token_uri(token_id).unwrap();
             assert_eq!(uri, token_uri);
         }
+
+        /// Minting past the last token ID fails instead of wrapping around.
+        #[ink::test]
+        fn minting_fails_when_ids_run_out() {
+            let mut nft = MinimalNft::new();
+            nft.token_count = u32::MAX;
+
+            assert_eq!(nft.mint(String::from("ipfs://test")), Err(Error::Overflow));
+            assert_eq!(nft.owner_of(1), None);
+            assert_eq!(nft.total_supply(), u32::MAX);
+        }
+    }
+
+    /// The shared NFT behaviour suite from `contracts/conformance`
+    #[cfg(test)]
+    mod behavior {
+        use super::*;
+        include!("../../conformance/nft_behavior.rs");
+
+        impl NftHarness for MinimalNft {
+            const NAME: &'static str = "minimal_nft";
+            const HAS_TRANSFER: bool = false;
+
+            fn deploy() -> Self {
+                MinimalNft::new()
+            }
+
+            fn mint(&mut self, to: AccountId) -> Option<u32> {
+                // Tokens are always minted to the caller
+                test::set_caller::<DefaultEnvironment>(to);
+                MinimalNft::mint(self, String::from("ipfs://QmBehavior")).ok()
+            }
+
+            fn transfer(&mut self, _to: AccountId, _token_id: u32) -> bool {
+                false
+            }
+
+            fn owner_of(&self, token_id: u32) -> Option<AccountId> {
+                MinimalNft::owner_of(self, token_id)
+            }
+
+            fn balance_of(&self, _owner: AccountId) -> Option<u32> {
+                None
+            }
+        }
+
+        #[test]
+        fn shared_behavior() {
+            run_behavior_suite::<MinimalNft>();
+        }
     }
 }
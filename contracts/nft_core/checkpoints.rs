@@ -0,0 +1,122 @@
+//! Per-account balance history shared by the collections, so votes can be
+//! weighed on holdings as of a past block. Each account has a list of
+//! checkpoints in block order, at most one per block, and lookups binary
+//! search it.
+
+use ink::primitives::AccountId;
+use ink::storage::{traits::StorageKey, Mapping};
+use scale::{Decode, Encode};
+
+/// Block number type of the default environment
+pub type BlockNumber = u32;
+
+/// An account's balance from `block` until the next checkpoint
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Checkpoint {
+    pub block: BlockNumber,
+    pub balance: u32,
+}
+
+/// Borrowed view over a contract's two checkpoint mappings
+pub struct Checkpoints<'a, C: StorageKey, N: StorageKey> {
+    /// Checkpoint at each `(account, position)`
+    pub checkpoints: &'a mut Mapping<(AccountId, u32), Checkpoint, C>,
+    /// Number of checkpoints per account
+    pub counts: &'a mut Mapping<AccountId, u32, N>,
+}
+
+impl<C: StorageKey, N: StorageKey> Checkpoints<'_, C, N> {
+    /// Get an account's latest recorded balance
+    pub fn latest(&self, account: AccountId) -> u32 {
+        self.counts
+            .get(account)
+            .and_then(|count| count.checked_sub(1))
+            .and_then(|last| self.checkpoints.get((account, last)))
+            .map_or(0, |checkpoint| checkpoint.balance)
+    }
+
+    /// Add to an account's latest balance as of `block`.
+    /// Returns `None`, changing nothing, if the balance can't grow.
+    pub fn increase(&mut self, account: AccountId, block: BlockNumber, amount: u32) -> Option<()> {
+        let balance = self.latest(account).checked_add(amount)?;
+        self.record(account, block, balance);
+        Some(())
+    }
+
+    /// Take from an account's latest balance as of `block`, stopping at zero
+    pub fn decrease(&mut self, account: AccountId, block: BlockNumber, amount: u32) {
+        let balance = self.latest(account).saturating_sub(amount);
+        self.record(account, block, balance);
+    }
+
+    /// Record an account's balance as of `block`, which must not be earlier
+    /// than its last checkpoint. A later change in the same block overwrites
+    /// that block's checkpoint.
+    pub fn record(&mut self, account: AccountId, block: BlockNumber, balance: u32) {
+        let count = self.counts.get(account).unwrap_or(0);
+        let last = count.checked_sub(1);
+        let same_block = last
+            .and_then(|last| self.checkpoints.get((account, last)))
+            .is_some_and(|checkpoint| checkpoint.block == block);
+
+        let position = match (last, count.checked_add(1)) {
+            (Some(last), _) if same_block => last,
+            (_, Some(new_count)) => {
+                self.counts.insert(account, &new_count);
+                count
+            }
+            // With one checkpoint per block, the count only stops growing
+            // once every block number has one
+            (last, None) => last.unwrap_or_default(),
+        };
+        self.checkpoints.insert((account, position), &Checkpoint { block, balance });
+    }
+}
+
+/// Get an account's balance at the end of `block`
+pub fn balance_at<C: StorageKey, N: StorageKey>(
+    checkpoints: &Mapping<(AccountId, u32), Checkpoint, C>,
+    counts: &Mapping<AccountId, u32, N>,
+    account: AccountId,
+    block: BlockNumber,
+) -> u32 {
+    // Find the first checkpoint after `block`
+    let (mut low, mut high) = (0u32, counts.get(account).unwrap_or(0));
+    while low < high {
+        let mid = low.saturating_add(high.saturating_sub(low).checked_div(2).unwrap_or(0));
+        match checkpoints.get((account, mid)) {
+            Some(checkpoint) if checkpoint.block > block => high = mid,
+            _ => low = mid.saturating_add(1),
+        }
+    }
+    low.checked_sub(1)
+        .and_then(|position| checkpoints.get((account, position)))
+        .map_or(0, |checkpoint| checkpoint.balance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[ink::test]
+    fn balance_at_finds_the_last_checkpoint_up_to_the_block() {
+        let mut checkpoints: Mapping<(AccountId, u32), Checkpoint> = Mapping::default();
+        let mut counts: Mapping<AccountId, u32> = Mapping::default();
+        let account = AccountId::from([1; 32]);
+        let mut history = Checkpoints {
+            checkpoints: &mut checkpoints,
+            counts: &mut counts,
+        };
+        history.record(account, 2, 1);
+        history.record(account, 5, 3);
+        history.record(account, 5, 2);
+        history.record(account, 9, 0);
+
+        let at = |block| balance_at(&checkpoints, &counts, account, block);
+        assert_eq!((at(0), at(1), at(2), at(4)), (0, 0, 1, 1));
+        assert_eq!((at(5), at(8), at(9), at(100)), (2, 2, 0, 0));
+        assert_eq!(counts.get(account), Some(3));
+        assert_eq!(balance_at(&checkpoints, &counts, AccountId::from([2; 32]), 5), 0);
+    }
+}
@@ -0,0 +1,78 @@
+//! Vote delegation shared by the collections. Every account's tokens vote
+//! through its representative: a delegate it picked, or itself. Each
+//! representative's voting power is checkpointed so votes can be weighed as
+//! of a past block.
+
+use crate::checkpoints::{BlockNumber, Checkpoints};
+use ink::primitives::AccountId;
+use ink::storage::{traits::StorageKey, Mapping};
+
+/// Borrowed view over a contract's delegates and vote checkpoints
+pub struct Delegation<'a, D: StorageKey, C: StorageKey, N: StorageKey> {
+    /// Delegate each account picked, if any
+    pub delegates: &'a mut Mapping<AccountId, AccountId, D>,
+    /// Voting power of each representative
+    pub votes: Checkpoints<'a, C, N>,
+}
+
+impl<D: StorageKey, C: StorageKey, N: StorageKey> Delegation<'_, D, C, N> {
+    /// Get the account that votes with `account`'s tokens
+    pub fn representative(&self, account: AccountId) -> AccountId {
+        self.delegates.get(account).unwrap_or(account)
+    }
+
+    /// Move voting power along with `amount` tokens going from `from` to
+    /// `to`; `None` stands for a mint or burn.
+    /// Returns `None`, changing nothing, if a representative's power can't grow.
+    pub fn move_tokens(
+        &mut self,
+        from: Option<AccountId>,
+        to: Option<AccountId>,
+        amount: u32,
+        block: BlockNumber,
+    ) -> Option<()> {
+        let from = from.map(|account| self.representative(account));
+        let to = to.map(|account| self.representative(account));
+        self.move_votes(from, to, amount, block)
+    }
+
+    /// Make `delegate` the representative of `account`, which holds
+    /// `balance` tokens; `None` has the account vote for itself again.
+    /// Returns `None`, changing nothing, if the new representative's power can't grow.
+    pub fn delegate(
+        &mut self,
+        account: AccountId,
+        delegate: Option<AccountId>,
+        balance: u32,
+        block: BlockNumber,
+    ) -> Option<()> {
+        let from = self.representative(account);
+        let to = delegate.unwrap_or(account);
+        self.move_votes(Some(from), Some(to), balance, block)?;
+        if to == account {
+            self.delegates.remove(account);
+        } else {
+            self.delegates.insert(account, &to);
+        }
+        Some(())
+    }
+
+    fn move_votes(
+        &mut self,
+        from: Option<AccountId>,
+        to: Option<AccountId>,
+        amount: u32,
+        block: BlockNumber,
+    ) -> Option<()> {
+        if from == to || amount == 0 {
+            return Some(());
+        }
+        if let Some(to) = to {
+            self.votes.increase(to, block, amount)?;
+        }
+        if let Some(from) = from {
+            self.votes.decrease(from, block, amount);
+        }
+        Some(())
+    }
+}
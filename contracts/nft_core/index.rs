@@ -0,0 +1,55 @@
+//! Per-owner token index shared by the collections. Each owner's tokens sit
+//! at positions `0..balance`; removals swap the last token into the freed
+//! slot so both enumeration and removal stay O(1).
+
+use ink::primitives::AccountId;
+use ink::storage::{traits::StorageKey, Mapping};
+
+/// Borrowed view over a contract's three index mappings
+pub struct OwnerIndex<'a, B: StorageKey, T: StorageKey, P: StorageKey> {
+    /// Number of tokens per owner, removed rather than stored as zero
+    pub balances: &'a mut Mapping<AccountId, u32, B>,
+    /// Token at each `(owner, position)`
+    pub tokens: &'a mut Mapping<(AccountId, u32), u32, T>,
+    /// Position of each token in its owner's list
+    pub positions: &'a mut Mapping<u32, u32, P>,
+}
+
+impl<B: StorageKey, T: StorageKey, P: StorageKey> OwnerIndex<'_, B, T, P> {
+    /// Append a token to the end of an owner's list.
+    /// Returns `None`, changing nothing, if the balance can't grow.
+    pub fn push(&mut self, owner: AccountId, token_id: u32) -> Option<()> {
+        let balance = self.balances.get(owner).unwrap_or(0);
+        let new_balance = balance.checked_add(1)?;
+        self.tokens.insert((owner, balance), &token_id);
+        self.positions.insert(token_id, &balance);
+        self.balances.insert(owner, &new_balance);
+        Some(())
+    }
+
+    /// Drop a token from an owner's list, swapping the last token into its slot.
+    /// Tokens that aren't indexed under `owner` are ignored.
+    pub fn remove(&mut self, owner: AccountId, token_id: u32) {
+        let (Some(balance), Some(position)) = (self.balances.get(owner), self.positions.get(token_id)) else {
+            return;
+        };
+        let Some(last) = balance.checked_sub(1) else {
+            return;
+        };
+
+        self.positions.remove(token_id);
+        if position != last {
+            if let Some(moved) = self.tokens.get((owner, last)) {
+                self.tokens.insert((owner, position), &moved);
+                self.positions.insert(moved, &position);
+            }
+        }
+        self.tokens.remove((owner, last));
+
+        if last == 0 {
+            self.balances.remove(owner);
+        } else {
+            self.balances.insert(owner, &last);
+        }
+    }
+}
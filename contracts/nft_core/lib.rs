@@ -0,0 +1,283 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::indexing_slicing
+    )
+)]
+
+//! VR Genesis Frame - Shared NFT Core
+//! PSP34-style trait definitions implemented by every ink! 4 collection in
+//! this repository, plus the bookkeeping they share. Callers can use the
+//! traits' selectors against any of the contracts interchangeably.
+//! `minimal_nft` targets ink! 5, so it can't implement these until it moves
+//! to the same ink! version as the rest.
+
+use ink::prelude::string::String;
+use ink::prelude::vec::Vec;
+use ink::primitives::AccountId;
+use scale::{Decode, Encode};
+
+pub mod checkpoints;
+pub mod delegation;
+pub mod index;
+pub mod rmrk;
+
+/// Balance type of the default environment
+pub type Balance = u128;
+
+/// Denominator for shares given in basis points
+pub const BASIS_POINTS: u16 = 10_000;
+
+/// Ownership queries every collection answers
+#[ink::trait_definition]
+pub trait PSP34Core {
+    /// Get the number of tokens held by `owner`
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId) -> u32;
+
+    /// Get the owner of a token
+    #[ink(message)]
+    fn owner_of(&self, token_id: u32) -> Option<AccountId>;
+
+    /// Check whether a token has been minted and not burned
+    #[ink(message)]
+    fn exists(&self, token_id: u32) -> bool;
+}
+
+/// Collection and token metadata
+#[ink::trait_definition]
+pub trait Metadata {
+    /// Get the name of the collection
+    #[ink(message)]
+    fn name(&self) -> String;
+
+    /// Get the symbol of the collection
+    #[ink(message)]
+    fn symbol(&self) -> String;
+
+    /// Get the URI for a token
+    #[ink(message)]
+    fn token_uri(&self, token_id: u32) -> Option<String>;
+}
+
+/// Past balances, so votes can be weighed on holdings as of a block
+#[ink::trait_definition]
+pub trait BalanceHistory {
+    /// Get the number of tokens `account` held at the end of `block`
+    #[ink(message)]
+    fn balance_at(&self, account: AccountId, block: checkpoints::BlockNumber) -> u32;
+}
+
+/// Voting power, counting the tokens of accounts that delegated to an account.
+/// Accounts that haven't delegated vote with their own tokens.
+#[ink::trait_definition]
+pub trait Votes {
+    /// Get the voting power of `account` at the end of `block`
+    #[ink(message)]
+    fn votes_at(&self, account: AccountId, block: checkpoints::BlockNumber) -> u32;
+
+    /// Get the account an account delegated its votes to
+    #[ink(message)]
+    fn delegate_of(&self, account: AccountId) -> Option<AccountId>;
+}
+
+/// Registries of accounts that attested to belonging to the same identity,
+/// so gates can count tokens across an identity's wallets
+#[ink::trait_definition]
+pub trait AccountLinks {
+    /// Get the other accounts linked to `account`
+    #[ink(message)]
+    fn linked_accounts(&self, account: AccountId) -> Vec<AccountId>;
+}
+
+/// Listing the tokens held by an account
+#[ink::trait_definition]
+pub trait Enumerable {
+    /// Get all tokens owned by an address
+    #[ink(message)]
+    fn tokens_of_owner(&self, owner: AccountId) -> Vec<u32>;
+
+    /// Get the token at `index` of an owner's list (`index < balance_of(owner)`)
+    #[ink(message)]
+    fn token_of_owner_by_index(&self, owner: AccountId, index: u32) -> Option<u32>;
+}
+
+/// Royalties owed on secondary sales
+#[ink::trait_definition]
+pub trait Royalty {
+    /// Get who is owed a royalty on a sale of `token_id` for `sale_price`,
+    /// and how much
+    #[ink(message)]
+    fn royalty_info(&self, token_id: u32, sale_price: Balance) -> Option<(AccountId, Balance)>;
+}
+
+/// Errors returned by PSP22 tokens
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP22Error {
+    Custom(String),
+    InsufficientBalance,
+    InsufficientAllowance,
+    ZeroRecipientAddress,
+    ZeroSenderAddress,
+    SafeTransferCheckFailed(String),
+}
+
+/// The part of the PSP22 fungible token standard collections take payments
+/// through. Selectors match the standard, so any PSP22 token can be called.
+#[ink::trait_definition]
+pub trait PSP22 {
+    /// Send `value` of the caller's tokens to `to`
+    #[ink(message)]
+    fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+    /// Send `value` of `from`'s tokens to `to`, spending the caller's allowance
+    #[ink(message)]
+    fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance, data: Vec<u8>)
+        -> Result<(), PSP22Error>;
+}
+
+/// Currency a price or payment is denominated in
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum Currency {
+    /// The chain's native token
+    Native,
+    /// A PSP22 token, by contract address
+    Psp22(AccountId),
+    /// An Asset Hub `pallet_assets` asset, such as bridged USDT, by asset ID.
+    /// Only contracts whose runtime provides the assets chain extension can
+    /// move these.
+    Asset(u32),
+}
+
+/// Oracles converting native prices into asset amounts, for collections
+/// that price assets off a feed instead of a fixed amount
+#[ink::trait_definition]
+pub trait PriceFeed {
+    /// Get what `native_amount` of the native token is worth in `asset_id`,
+    /// or `None` if the feed has no fresh price for it
+    #[ink(message)]
+    fn quote(&self, asset_id: u32, native_amount: Balance) -> Option<Balance>;
+}
+
+/// One USD in the units USD prices are given in (six decimals, as USDT)
+pub const ONE_USD: Balance = 1_000_000;
+
+/// A currency's price as reported by an oracle
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct OraclePrice {
+    /// Base units of the currency one USD buys
+    pub units_per_usd: Balance,
+    /// Timestamp (milliseconds) the price was last updated at
+    pub updated_at: u64,
+}
+
+/// Adapters in front of price oracles such as DIA, so collections can price
+/// in USD and convert at call time whatever the oracle's own interface
+#[ink::trait_definition]
+pub trait OracleAdapter {
+    /// Get the latest USD price of `currency`, or `None` if the oracle doesn't track it
+    #[ink(message)]
+    fn latest_price(&self, currency: Currency) -> Option<OraclePrice>;
+}
+
+/// Convert a USD amount (in `ONE_USD` units) into base units of a currency
+/// at `units_per_usd`, rounding down. `None` on overflow.
+pub fn usd_to_units(usd: Balance, units_per_usd: Balance) -> Option<Balance> {
+    let whole = usd.checked_div(ONE_USD)?.checked_mul(units_per_usd)?;
+    let fraction = usd.checked_rem(ONE_USD)?.checked_mul(units_per_usd)?.checked_div(ONE_USD)?;
+    whole.checked_add(fraction)
+}
+
+/// An NFT living in any collection contract
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct ItemRef {
+    /// Contract address of the item's collection
+    pub collection: AccountId,
+    /// Token ID within the collection
+    pub token_id: u32,
+}
+
+/// Contracts that anchor NFTs from other collections into scenes
+#[ink::trait_definition]
+pub trait SceneContainer {
+    /// Get the parcel an item is anchored in
+    #[ink(message)]
+    fn placement_of(&self, item: ItemRef) -> Option<u32>;
+
+    /// Check whether an account may enter a parcel
+    #[ink(message)]
+    fn can_enter(&self, account: AccountId, parcel_id: u32) -> bool;
+}
+
+/// Callbacks a collection makes around its transfers. The calling contract
+/// is the collection, so implementations can trust `caller()` to name it.
+/// `from` is `None` for mints and `to` is `None` for burns.
+#[ink::trait_definition]
+pub trait TransferHook {
+    /// Called before a transfer; returning `false` asks the collection to refuse it
+    #[ink(message)]
+    fn before_transfer(&mut self, from: Option<AccountId>, to: Option<AccountId>, token_id: u32) -> bool;
+
+    /// Called once a transfer has happened
+    #[ink(message)]
+    fn after_transfer(&mut self, from: Option<AccountId>, to: Option<AccountId>, token_id: u32);
+}
+
+/// Governance contracts that count sacrificed tokens as amplified votes.
+/// The calling contract is the collection, which has already burned or
+/// locked the token, so implementations can trust `caller()` to name it.
+#[ink::trait_definition]
+pub trait SacrificeReceiver {
+    /// Called once `holder` burned (`burned`) or locked `token_id` to vote on
+    /// `proposal_id`; returning `false` asks the collection to undo it
+    #[ink(message)]
+    fn on_sacrifice(&mut self, holder: AccountId, token_id: u32, proposal_id: u32, support: bool, burned: bool) -> bool;
+}
+
+/// The part of `price` owed for a share given in basis points, rounded down
+pub fn royalty_amount(price: Balance, share_bps: u16) -> Balance {
+    let share = Balance::from(share_bps.min(BASIS_POINTS));
+    let bps = Balance::from(BASIS_POINTS);
+    // Split the price first so the multiplication can't overflow
+    let whole = price.checked_div(bps).unwrap_or(0);
+    let rest = price.checked_rem(bps).unwrap_or(0);
+    whole
+        .saturating_mul(share)
+        .saturating_add(rest.saturating_mul(share).checked_div(bps).unwrap_or(0))
+}
+
+/// Whether `to` can receive a token held by `contract`. The zero address and
+/// the contract itself could never move it again.
+pub fn is_valid_recipient(to: &AccountId, contract: &AccountId) -> bool {
+    *to != AccountId::from([0u8; 32]) && to != contract
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn royalty_amount_rounds_down_without_overflow() {
+        assert_eq!(royalty_amount(1_000, 250), 25);
+        assert_eq!(royalty_amount(9_999, 1), 0);
+        assert_eq!(royalty_amount(Balance::MAX, BASIS_POINTS), Balance::MAX);
+        assert_eq!(royalty_amount(1_000, u16::MAX), 1_000);
+    }
+
+    #[test]
+    fn usd_to_units_keeps_fractions_without_overflow() {
+        // 2.5 USD at 0.2 DOT (10 decimals) per USD
+        assert_eq!(usd_to_units(2_500_000, 2_000_000_000), Some(5_000_000_000));
+        assert_eq!(usd_to_units(1, 999_999), Some(0));
+        assert_eq!(usd_to_units(Balance::MAX, 1), Some(Balance::MAX / ONE_USD));
+        assert_eq!(usd_to_units(Balance::MAX, 2 * ONE_USD), None);
+    }
+}
@@ -0,0 +1,100 @@
+//! Interfaces of RMRK 2.0 ink! collections, so avatars here can hold NFTs
+//! from RMRK collections and RMRK tooling can nest into collections here.
+//! Trait and type names follow RMRK's, which gives the same selectors and
+//! encodings as its OpenBrush-based contracts.
+
+use ink::prelude::string::String;
+use ink::prelude::vec::Vec;
+use ink::primitives::AccountId;
+use scale::{Decode, Encode};
+
+/// PSP34 token ID, which RMRK collections use in place of plain integers
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum Id {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    Bytes(Vec<u8>),
+}
+
+impl Id {
+    /// The ID as a `u32` token ID of the collections here, if it is one
+    pub fn as_u32(&self) -> Option<u32> {
+        match *self {
+            Id::U8(id) => Some(u32::from(id)),
+            Id::U16(id) => Some(u32::from(id)),
+            Id::U32(id) => Some(id),
+            Id::U64(id) => u32::try_from(id).ok(),
+            Id::U128(id) => u32::try_from(id).ok(),
+            Id::Bytes(_) => None,
+        }
+    }
+}
+
+/// A nested NFT: its collection's contract address and its ID there
+pub type ChildNft = (AccountId, Id);
+
+/// Errors returned by PSP34 and RMRK calls. RMRK reports its own errors as
+/// `Custom` with the error's name.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP34Error {
+    Custom(String),
+    SelfApprove,
+    NotApproved,
+    TokenExists,
+    TokenNotExists,
+    SafeTransferCheckFailed(String),
+}
+
+impl PSP34Error {
+    /// An RMRK error, reported by name as RMRK collections do
+    pub fn rmrk(name: &str) -> Self {
+        Self::Custom(String::from(name))
+    }
+}
+
+/// The part of PSP34 a parent uses to take custody of a child and give it back
+#[ink::trait_definition]
+pub trait PSP34 {
+    /// Get the owner of `id`
+    #[ink(message)]
+    fn owner_of(&self, id: Id) -> Option<AccountId>;
+
+    /// Send `id` to `to`; the caller must own it or be approved for it
+    #[ink(message)]
+    fn transfer(&mut self, to: AccountId, id: Id, data: Vec<u8>) -> Result<(), PSP34Error>;
+}
+
+/// RMRK 2.0 nesting: parents own children held in custody by the parent's
+/// contract. Children added by someone other than the parent's owner wait
+/// as pending until the owner accepts or rejects them.
+#[ink::trait_definition]
+pub trait Nesting {
+    /// Take custody of `child_nft` from the caller and nest it under `parent_token_id`
+    #[ink(message)]
+    fn add_child(&mut self, parent_token_id: Id, child_nft: ChildNft) -> Result<(), PSP34Error>;
+
+    /// Un-nest an accepted child and send it to the caller (parent owner only)
+    #[ink(message)]
+    fn remove_child(&mut self, parent_token_id: Id, child_nft: ChildNft) -> Result<(), PSP34Error>;
+
+    /// Accept a pending child (parent owner only)
+    #[ink(message)]
+    fn accept_child(&mut self, parent_token_id: Id, child_nft: ChildNft) -> Result<(), PSP34Error>;
+
+    /// Turn a pending child away (parent owner only)
+    #[ink(message)]
+    fn reject_child(&mut self, parent_token_id: Id, child_nft: ChildNft) -> Result<(), PSP34Error>;
+
+    /// Move an accepted child from one parent to another (owner of `from` only)
+    #[ink(message)]
+    fn transfer_child(&mut self, from: Id, to: Id, child_nft: ChildNft) -> Result<(), PSP34Error>;
+
+    /// Get a parent's numbers of accepted and pending children
+    #[ink(message)]
+    fn children_balance(&self, parent_token_id: Id) -> Result<(u64, u64), PSP34Error>;
+}
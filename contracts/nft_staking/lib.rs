@@ -0,0 +1,436 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::indexing_slicing
+    )
+)]
+
+/// VR Genesis Frame - NFT Staking
+/// Holders of a `PSP34Core` collection (VR land parcels, frames) stake tokens
+/// to earn native-token rewards paid per block from a pool anyone can fund.
+/// Staked tokens stay in the holder's account. Collections that call transfer
+/// hooks lock them while staked; for the rest, rewards are only paid while the
+/// staker still owns the token.
+#[ink::contract]
+pub mod nft_staking {
+    use ink::codegen::TraitCallBuilder;
+    use ink::storage::Mapping;
+    use polka_space_nft_core::{PSP34Core, TransferHook};
+    use scale::{Decode, Encode};
+
+    /// A staked token
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Stake {
+        pub staker: AccountId,
+        /// Block the token was staked in
+        pub since: BlockNumber,
+        /// `reward_per_token` as of the stake or its last claim
+        pub reward_per_token_paid: Balance,
+    }
+
+    /// Event emitted when a token is staked
+    #[ink(event)]
+    pub struct Staked {
+        #[ink(topic)]
+        staker: AccountId,
+        #[ink(topic)]
+        token_id: u32,
+    }
+
+    /// Event emitted when a token is unstaked, with the reward paid out
+    #[ink(event)]
+    pub struct Unstaked {
+        #[ink(topic)]
+        staker: AccountId,
+        #[ink(topic)]
+        token_id: u32,
+        reward: Balance,
+    }
+
+    /// Event emitted when a staker claims rewards without unstaking
+    #[ink(event)]
+    pub struct RewardsClaimed {
+        #[ink(topic)]
+        staker: AccountId,
+        #[ink(topic)]
+        token_id: u32,
+        amount: Balance,
+    }
+
+    /// Event emitted when the reward pool is topped up
+    #[ink(event)]
+    pub struct PoolFunded {
+        #[ink(topic)]
+        from: AccountId,
+        amount: Balance,
+    }
+
+    /// Errors returned by the staking contract
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Caller does not own the token in the collection
+        NotOwner,
+        /// The token is not staked
+        NotStaked,
+        /// The token is staked by another account
+        NotStaker,
+        /// The caller already staked the token
+        AlreadyStaked,
+        /// Caller is not the contract admin
+        NotAdmin,
+        /// The reward pool can't cover the rewards owed
+        InsufficientPool,
+        /// Paying out native tokens failed
+        TransferFailed,
+        /// A counter or balance would overflow
+        Overflow,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    pub struct NftStaking {
+        /// Account that sets the reward rate
+        admin: AccountId,
+        /// Collection whose tokens can be staked
+        collection: AccountId,
+        /// Reward earned by each staked token per block
+        reward_per_block: Balance,
+        /// Reward a token staked since deployment would have earned, as of `last_update`
+        reward_per_token: Balance,
+        /// Block `reward_per_token` was last brought up to date
+        last_update: BlockNumber,
+        /// Funds available for rewards
+        reward_pool: Balance,
+        stakes: Mapping<u32, Stake>,
+        total_staked: u32,
+    }
+
+    impl NftStaking {
+        /// Create a staking contract for `collection`, administered by the caller
+        #[ink(constructor)]
+        pub fn new(collection: AccountId, reward_per_block: Balance) -> Self {
+            Self {
+                admin: Self::env().caller(),
+                collection,
+                reward_per_block,
+                reward_per_token: 0,
+                last_update: Self::env().block_number(),
+                reward_pool: 0,
+                stakes: Mapping::default(),
+                total_staked: 0,
+            }
+        }
+
+        /// Get the admin account
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// Get the collection whose tokens can be staked
+        #[ink(message)]
+        pub fn collection(&self) -> AccountId {
+            self.collection
+        }
+
+        /// Get the reward each staked token earns per block
+        #[ink(message)]
+        pub fn reward_per_block(&self) -> Balance {
+            self.reward_per_block
+        }
+
+        /// Get the funds left for rewards
+        #[ink(message)]
+        pub fn reward_pool(&self) -> Balance {
+            self.reward_pool
+        }
+
+        /// Get the number of staked tokens
+        #[ink(message)]
+        pub fn total_staked(&self) -> u32 {
+            self.total_staked
+        }
+
+        /// Get a token's stake
+        #[ink(message)]
+        pub fn stake_of(&self, token_id: u32) -> Option<Stake> {
+            self.stakes.get(token_id)
+        }
+
+        /// Get the rewards a staked token has earned but not yet paid out
+        #[ink(message)]
+        pub fn pending_rewards(&self, token_id: u32) -> Balance {
+            self.stakes
+                .get(token_id)
+                .map(|stake| self.current_reward_per_token().saturating_sub(stake.reward_per_token_paid))
+                .unwrap_or(0)
+        }
+
+        /// Change the per-block reward (admin only). Rewards already earned
+        /// keep the old rate.
+        #[ink(message)]
+        pub fn set_reward_per_block(&mut self, reward_per_block: Balance) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAdmin);
+            }
+            self.reward_per_token = self.current_reward_per_token();
+            self.last_update = self.env().block_number();
+            self.reward_per_block = reward_per_block;
+            Ok(())
+        }
+
+        /// Add the value sent to the reward pool
+        #[ink(message, payable)]
+        pub fn fund(&mut self) -> Result<()> {
+            let amount = self.env().transferred_value();
+            self.reward_pool = self.reward_pool.checked_add(amount).ok_or(Error::Overflow)?;
+
+            self.env().emit_event(PoolFunded {
+                from: self.env().caller(),
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Stake a token the caller owns. A stake left behind by a previous
+        /// owner is replaced, forfeiting its unpaid rewards.
+        #[ink(message)]
+        pub fn stake(&mut self, token_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+            self.ensure_holder(caller, token_id)?;
+            self.open_stake(caller, token_id)
+        }
+
+        /// Claim a staked token's rewards (staker only, while they own it)
+        #[ink(message)]
+        pub fn claim_rewards(&mut self, token_id: u32) -> Result<Balance> {
+            let caller = self.env().caller();
+            self.ensure_staker(caller, token_id)?;
+            self.ensure_holder(caller, token_id)?;
+            self.claim_for(caller, token_id)
+        }
+
+        /// Unstake a token, paying out its rewards (staker only, while they
+        /// own it). If the pool runs short, whatever is left is paid and the
+        /// rest forfeited, so a staker can always leave.
+        #[ink(message)]
+        pub fn unstake(&mut self, token_id: u32) -> Result<Balance> {
+            let caller = self.env().caller();
+            self.ensure_staker(caller, token_id)?;
+            self.ensure_holder(caller, token_id)?;
+            self.unstake_for(caller, token_id)
+        }
+
+        /// `reward_per_token` brought up to the current block
+        fn current_reward_per_token(&self) -> Balance {
+            let blocks = self.env().block_number().saturating_sub(self.last_update);
+            self.reward_per_token
+                .saturating_add(Balance::from(blocks).saturating_mul(self.reward_per_block))
+        }
+
+        /// Check that `account` owns the token in the collection; failed calls count as not owning it
+        fn ensure_holder(&self, account: AccountId, token_id: u32) -> Result<()> {
+            let collection: ink::contract_ref!(PSP34Core) = self.collection.into();
+            let owner = collection
+                .call()
+                .owner_of(token_id)
+                .try_invoke()
+                .ok()
+                .and_then(|result| result.ok())
+                .flatten();
+            if owner != Some(account) {
+                return Err(Error::NotOwner);
+            }
+            Ok(())
+        }
+
+        /// Get the stake of a token staked by `account`
+        fn ensure_staker(&self, account: AccountId, token_id: u32) -> Result<Stake> {
+            let stake = self.stakes.get(token_id).ok_or(Error::NotStaked)?;
+            if stake.staker != account {
+                return Err(Error::NotStaker);
+            }
+            Ok(stake)
+        }
+
+        /// Record a stake for a holder whose ownership has been checked
+        fn open_stake(&mut self, staker: AccountId, token_id: u32) -> Result<()> {
+            match self.stakes.get(token_id) {
+                Some(stake) if stake.staker == staker => return Err(Error::AlreadyStaked),
+                Some(_) => {}
+                None => self.total_staked = self.total_staked.checked_add(1).ok_or(Error::Overflow)?,
+            }
+
+            self.stakes.insert(
+                token_id,
+                &Stake {
+                    staker,
+                    since: self.env().block_number(),
+                    reward_per_token_paid: self.current_reward_per_token(),
+                },
+            );
+
+            self.env().emit_event(Staked { staker, token_id });
+            Ok(())
+        }
+
+        /// Pay out a stake's rewards to a staker whose ownership has been checked
+        fn claim_for(&mut self, staker: AccountId, token_id: u32) -> Result<Balance> {
+            let mut stake = self.ensure_staker(staker, token_id)?;
+            let amount = self.pending_rewards(token_id);
+            if amount > self.reward_pool {
+                return Err(Error::InsufficientPool);
+            }
+
+            stake.reward_per_token_paid = self.current_reward_per_token();
+            self.stakes.insert(token_id, &stake);
+            self.pay_out(staker, amount)?;
+
+            self.env().emit_event(RewardsClaimed { staker, token_id, amount });
+            Ok(amount)
+        }
+
+        /// Close a stake for a staker whose ownership has been checked
+        fn unstake_for(&mut self, staker: AccountId, token_id: u32) -> Result<Balance> {
+            self.ensure_staker(staker, token_id)?;
+            let reward = self.pending_rewards(token_id).min(self.reward_pool);
+
+            self.stakes.remove(token_id);
+            self.total_staked = self.total_staked.saturating_sub(1);
+            self.pay_out(staker, reward)?;
+
+            self.env().emit_event(Unstaked { staker, token_id, reward });
+            Ok(reward)
+        }
+
+        /// Pay `amount` from the reward pool; state is updated before the transfer
+        fn pay_out(&mut self, to: AccountId, amount: Balance) -> Result<()> {
+            if amount == 0 {
+                return Ok(());
+            }
+            self.reward_pool = self.reward_pool.checked_sub(amount).ok_or(Error::InsufficientPool)?;
+            if self.env().transfer(to, amount).is_err() {
+                return Err(Error::TransferFailed);
+            }
+            Ok(())
+        }
+    }
+
+    impl TransferHook for NftStaking {
+        /// Lock staked tokens, when registered as a hook on the collection
+        #[ink(message)]
+        fn before_transfer(&mut self, _from: Option<AccountId>, _to: Option<AccountId>, token_id: u32) -> bool {
+            self.env().caller() != self.collection || !self.stakes.contains(token_id)
+        }
+
+        /// Nothing to do once a transfer went through
+        #[ink(message)]
+        fn after_transfer(&mut self, _from: Option<AccountId>, _to: Option<AccountId>, _token_id: u32) {}
+    }
+
+    /// Unit tests. The off-chain environment can't call the collection, so
+    /// these drive the helpers behind the ownership check directly.
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        fn new_staking(reward_per_block: Balance) -> NftStaking {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            test::set_callee::<DefaultEnvironment>(accounts.django);
+            NftStaking::new(accounts.frank, reward_per_block)
+        }
+
+        fn advance_blocks(count: u32) {
+            for _ in 0..count {
+                test::advance_block::<DefaultEnvironment>();
+            }
+        }
+
+        #[ink::test]
+        fn rewards_accrue_per_block_and_pay_from_the_pool() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut staking = new_staking(10);
+            test::set_account_balance::<DefaultEnvironment>(accounts.django, 1_000);
+            test::set_value_transferred::<DefaultEnvironment>(1_000);
+            assert_eq!(staking.fund(), Ok(()));
+            assert_eq!(staking.reward_pool(), 1_000);
+
+            assert_eq!(staking.open_stake(accounts.bob, 7), Ok(()));
+            assert_eq!(staking.open_stake(accounts.bob, 7), Err(Error::AlreadyStaked));
+            assert_eq!(staking.total_staked(), 1);
+            advance_blocks(3);
+            assert_eq!(staking.pending_rewards(7), 30);
+
+            // Rate changes only apply from the block they're made in
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(staking.set_reward_per_block(100), Err(Error::NotAdmin));
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(staking.set_reward_per_block(20), Ok(()));
+            advance_blocks(2);
+            assert_eq!(staking.pending_rewards(7), 70);
+
+            let balance = test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap();
+            assert_eq!(staking.claim_for(accounts.charlie, 7), Err(Error::NotStaker));
+            assert_eq!(staking.claim_for(accounts.bob, 7), Ok(70));
+            assert_eq!(test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap(), balance + 70);
+            assert_eq!(staking.pending_rewards(7), 0);
+            assert_eq!(staking.reward_pool(), 930);
+
+            advance_blocks(1);
+            assert_eq!(staking.unstake_for(accounts.bob, 7), Ok(20));
+            assert_eq!(staking.stake_of(7), None);
+            assert_eq!(staking.total_staked(), 0);
+            assert_eq!(staking.unstake_for(accounts.bob, 7), Err(Error::NotStaked));
+        }
+
+        #[ink::test]
+        fn short_pool_blocks_claims_but_not_unstaking() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut staking = new_staking(10);
+            test::set_account_balance::<DefaultEnvironment>(accounts.django, 25);
+            test::set_value_transferred::<DefaultEnvironment>(25);
+            assert_eq!(staking.fund(), Ok(()));
+            assert_eq!(staking.open_stake(accounts.bob, 1), Ok(()));
+            advance_blocks(3);
+
+            assert_eq!(staking.claim_for(accounts.bob, 1), Err(Error::InsufficientPool));
+            assert_eq!(staking.unstake_for(accounts.bob, 1), Ok(25));
+            assert_eq!(staking.reward_pool(), 0);
+        }
+
+        #[ink::test]
+        fn new_owners_replace_stale_stakes() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut staking = new_staking(10);
+            assert_eq!(staking.open_stake(accounts.bob, 1), Ok(()));
+            advance_blocks(2);
+
+            // Bob sold the token without a hook; Charlie's stake starts from zero
+            assert_eq!(staking.open_stake(accounts.charlie, 1), Ok(()));
+            assert_eq!(staking.stake_of(1).map(|stake| stake.staker), Some(accounts.charlie));
+            assert_eq!(staking.pending_rewards(1), 0);
+            assert_eq!(staking.total_staked(), 1);
+        }
+
+        #[ink::test]
+        fn staked_tokens_are_locked_for_the_collection_only() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut staking = new_staking(10);
+            assert_eq!(staking.open_stake(accounts.bob, 1), Ok(()));
+
+            test::set_caller::<DefaultEnvironment>(accounts.frank);
+            assert!(!staking.before_transfer(Some(accounts.bob), Some(accounts.charlie), 1));
+            assert!(staking.before_transfer(Some(accounts.bob), Some(accounts.charlie), 2));
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert!(staking.before_transfer(Some(accounts.bob), Some(accounts.charlie), 1));
+        }
+    }
+}
@@ -0,0 +1,566 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::indexing_slicing
+    )
+)]
+
+/// VR Genesis Frame - IPFS Pinning Bonds
+/// Anyone can bond native tokens against the IPFS CID of a 3D asset as a
+/// reward pool for keeping it pinned. Registered pinning providers prove they
+/// still hold the file once per period by answering a hash challenge: the
+/// pool's opener commits to a Merkle root over the file's `CHUNK_SIZE`
+/// chunks, each period asks each provider for one chunk, and a chunk with a
+/// path to the root earns that period's reward.
+///
+/// Challenges are derived from the period and the provider, so they can be
+/// computed ahead; a provider dropping the file keeps only what future
+/// periods ask for, which for long-lived pools is most of it.
+#[ink::contract]
+pub mod pinning_bonds {
+    use ink::codegen::TraitCallBuilder;
+    use ink::env::hash::{Blake2x256, HashOutput};
+    use ink::prelude::string::String;
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use polka_space_nft_core::Metadata;
+    use scale::{Decode, Encode};
+
+    /// Bytes per chunk the file is split into for challenges; the last one may be shorter
+    pub const CHUNK_SIZE: usize = 1024;
+
+    /// Longest CID accepted
+    pub const MAX_CID_LEN: usize = 128;
+
+    /// URI scheme stripped from token URIs to get their CID
+    pub const IPFS_SCHEME: &str = "ipfs://";
+
+    /// A CID's reward pool
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Pool {
+        /// Merkle root over the Blake2x256 hashes of the file's chunks, pairing
+        /// an odd last node with itself
+        pub chunk_root: Hash,
+        pub chunk_count: u32,
+        /// Paid to each provider for each period they prove
+        pub reward_per_period: Balance,
+        /// Funds bonded and not yet paid out
+        pub balance: Balance,
+    }
+
+    /// A provider's proofs for one CID
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq, Default)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct ProviderRecord {
+        /// Period of the latest accepted proof
+        pub last_proven: Option<u32>,
+        /// Proven periods not yet paid
+        pub unclaimed: u32,
+    }
+
+    /// Event emitted when a CID's pool is opened
+    #[ink(event)]
+    pub struct PoolOpened {
+        #[ink(topic)]
+        cid: String,
+        chunk_root: Hash,
+        chunk_count: u32,
+        reward_per_period: Balance,
+    }
+
+    /// Event emitted when funds are bonded to a pool
+    #[ink(event)]
+    pub struct Bonded {
+        #[ink(topic)]
+        cid: String,
+        #[ink(topic)]
+        from: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when the admin registers or removes a provider
+    #[ink(event)]
+    pub struct ProviderChanged {
+        #[ink(topic)]
+        provider: AccountId,
+        registered: bool,
+    }
+
+    /// Event emitted when a provider answers a period's challenge
+    #[ink(event)]
+    pub struct ProofAccepted {
+        #[ink(topic)]
+        cid: String,
+        #[ink(topic)]
+        provider: AccountId,
+        period: u32,
+    }
+
+    /// Event emitted when a provider is paid for proven periods
+    #[ink(event)]
+    pub struct RewardsClaimed {
+        #[ink(topic)]
+        cid: String,
+        #[ink(topic)]
+        provider: AccountId,
+        periods: u32,
+        amount: Balance,
+    }
+
+    /// Errors returned by the pinning bond registry
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Caller is not the contract admin
+        NotAdmin,
+        /// Caller is not a registered pinning provider
+        NotProvider,
+        /// The CID is empty or longer than `MAX_CID_LEN`
+        InvalidCid,
+        /// The token has no `ipfs://` URI in the collection
+        NoCid,
+        /// The CID already has a pool
+        PoolExists,
+        /// The CID has no pool
+        PoolNotFound,
+        /// A pool needs chunks, a reward per period and some funds
+        InvalidPool,
+        /// The provider already proved this period
+        AlreadyProven,
+        /// The chunk is too long or has no path to the pool's root
+        InvalidProof,
+        /// Paying out native tokens failed
+        TransferFailed,
+        /// A counter or balance would overflow
+        Overflow,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    pub struct PinningBonds {
+        /// Account that registers providers
+        admin: AccountId,
+        /// Collection whose tokens' CIDs `bond_token` looks up
+        collection: AccountId,
+        /// Blocks per challenge period
+        period_length: BlockNumber,
+        pools: Mapping<String, Pool>,
+        providers: Mapping<AccountId, ()>,
+        /// Proofs of each provider, keyed by (CID, provider)
+        records: Mapping<(String, AccountId), ProviderRecord>,
+    }
+
+    impl PinningBonds {
+        /// Create a registry for `collection`'s assets, administered by the caller
+        #[ink(constructor)]
+        pub fn new(collection: AccountId, period_length: BlockNumber) -> Self {
+            Self {
+                admin: Self::env().caller(),
+                collection,
+                period_length: period_length.max(1),
+                pools: Mapping::default(),
+                providers: Mapping::default(),
+                records: Mapping::default(),
+            }
+        }
+
+        /// Get the admin account
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// Get the collection whose tokens' CIDs `bond_token` looks up
+        #[ink(message)]
+        pub fn collection(&self) -> AccountId {
+            self.collection
+        }
+
+        /// Get the number of blocks per challenge period
+        #[ink(message)]
+        pub fn period_length(&self) -> BlockNumber {
+            self.period_length
+        }
+
+        /// Get the current challenge period
+        #[ink(message)]
+        pub fn current_period(&self) -> u32 {
+            self.env().block_number().checked_div(self.period_length).unwrap_or(0)
+        }
+
+        /// Get a CID's pool
+        #[ink(message)]
+        pub fn pool(&self, cid: String) -> Option<Pool> {
+            self.pools.get(cid)
+        }
+
+        /// Check whether an account is a registered pinning provider
+        #[ink(message)]
+        pub fn is_provider(&self, account: AccountId) -> bool {
+            self.providers.contains(account)
+        }
+
+        /// Get a provider's proofs for a CID
+        #[ink(message)]
+        pub fn record_of(&self, cid: String, provider: AccountId) -> Option<ProviderRecord> {
+            self.records.get((cid, provider))
+        }
+
+        /// Get the chunk index a provider must prove for a CID this period
+        #[ink(message)]
+        pub fn challenge(&self, cid: String, provider: AccountId) -> Option<u32> {
+            let pool = self.pools.get(&cid)?;
+            Some(self.challenge_index(&pool, provider, self.current_period()))
+        }
+
+        /// Register or remove a pinning provider (admin only). Removed
+        /// providers can still claim periods they proved.
+        #[ink(message)]
+        pub fn set_provider(&mut self, provider: AccountId, registered: bool) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAdmin);
+            }
+            if registered {
+                self.providers.insert(provider, &());
+            } else {
+                self.providers.remove(provider);
+            }
+            self.env().emit_event(ProviderChanged { provider, registered });
+            Ok(())
+        }
+
+        /// Open a CID's pool with the value sent, committing to the file's
+        /// chunk Merkle root and the reward paid per proven period
+        #[ink(message, payable)]
+        pub fn open_pool(
+            &mut self,
+            cid: String,
+            chunk_root: Hash,
+            chunk_count: u32,
+            reward_per_period: Balance,
+        ) -> Result<()> {
+            Self::ensure_cid(&cid)?;
+            let balance = self.env().transferred_value();
+            if chunk_count == 0 || reward_per_period == 0 || balance == 0 {
+                return Err(Error::InvalidPool);
+            }
+            if self.pools.contains(&cid) {
+                return Err(Error::PoolExists);
+            }
+            self.pools.insert(
+                &cid,
+                &Pool {
+                    chunk_root,
+                    chunk_count,
+                    reward_per_period,
+                    balance,
+                },
+            );
+
+            self.env().emit_event(PoolOpened {
+                cid: cid.clone(),
+                chunk_root,
+                chunk_count,
+                reward_per_period,
+            });
+            self.env().emit_event(Bonded {
+                cid,
+                from: self.env().caller(),
+                amount: balance,
+            });
+            Ok(())
+        }
+
+        /// Add the value sent to a CID's pool. Bonds can't be withdrawn.
+        #[ink(message, payable)]
+        pub fn bond(&mut self, cid: String) -> Result<()> {
+            let mut pool = self.pools.get(&cid).ok_or(Error::PoolNotFound)?;
+            let amount = self.env().transferred_value();
+            pool.balance = pool.balance.checked_add(amount).ok_or(Error::Overflow)?;
+            self.pools.insert(&cid, &pool);
+
+            self.env().emit_event(Bonded {
+                cid,
+                from: self.env().caller(),
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Add the value sent to the pool of a collection token's CID
+        #[ink(message, payable)]
+        pub fn bond_token(&mut self, token_id: u32) -> Result<()> {
+            let cid = self.token_cid(token_id)?;
+            self.bond(cid)
+        }
+
+        /// Answer this period's challenge for a CID with the challenged chunk
+        /// and the sibling hashes on its path to the pool's root, leaf first
+        #[ink(message)]
+        pub fn submit_proof(&mut self, cid: String, chunk: Vec<u8>, proof: Vec<Hash>) -> Result<()> {
+            let provider = self.env().caller();
+            if !self.providers.contains(provider) {
+                return Err(Error::NotProvider);
+            }
+            let pool = self.pools.get(&cid).ok_or(Error::PoolNotFound)?;
+            let period = self.current_period();
+            let key = (cid, provider);
+            let mut record = self.records.get(&key).unwrap_or_default();
+            if record.last_proven == Some(period) {
+                return Err(Error::AlreadyProven);
+            }
+            let index = self.challenge_index(&pool, provider, period);
+            if !Self::verify_chunk(&pool, index, &chunk, &proof) {
+                return Err(Error::InvalidProof);
+            }
+
+            record.last_proven = Some(period);
+            record.unclaimed = record.unclaimed.checked_add(1).ok_or(Error::Overflow)?;
+            self.records.insert(&key, &record);
+
+            let (cid, provider) = key;
+            self.env().emit_event(ProofAccepted { cid, provider, period });
+            Ok(())
+        }
+
+        /// Pay the caller for the periods they proved for a CID, as many as
+        /// the pool covers; the rest stay claimable once it's topped up
+        #[ink(message)]
+        pub fn claim(&mut self, cid: String) -> Result<Balance> {
+            let provider = self.env().caller();
+            let mut pool = self.pools.get(&cid).ok_or(Error::PoolNotFound)?;
+            let key = (cid, provider);
+            let mut record = self.records.get(&key).unwrap_or_default();
+            let affordable = pool.balance.checked_div(pool.reward_per_period).unwrap_or(0);
+            let periods = record.unclaimed.min(u32::try_from(affordable).unwrap_or(u32::MAX));
+            if periods == 0 {
+                return Ok(0);
+            }
+            let amount = pool.reward_per_period.checked_mul(Balance::from(periods)).ok_or(Error::Overflow)?;
+
+            pool.balance = pool.balance.checked_sub(amount).ok_or(Error::Overflow)?;
+            record.unclaimed = record.unclaimed.saturating_sub(periods);
+            self.pools.insert(&key.0, &pool);
+            self.records.insert(&key, &record);
+            if self.env().transfer(provider, amount).is_err() {
+                return Err(Error::TransferFailed);
+            }
+
+            let (cid, provider) = key;
+            self.env().emit_event(RewardsClaimed {
+                cid,
+                provider,
+                periods,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Check that a CID fits in storage
+        fn ensure_cid(cid: &str) -> Result<()> {
+            if cid.is_empty() || cid.len() > MAX_CID_LEN {
+                return Err(Error::InvalidCid);
+            }
+            Ok(())
+        }
+
+        /// Look up a token's CID from its `ipfs://` URI in the collection
+        fn token_cid(&self, token_id: u32) -> Result<String> {
+            let collection: ink::contract_ref!(Metadata) = self.collection.into();
+            let uri = collection
+                .call()
+                .token_uri(token_id)
+                .try_invoke()
+                .ok()
+                .and_then(|result| result.ok())
+                .flatten()
+                .ok_or(Error::NoCid)?;
+            Self::cid_from_uri(&uri).ok_or(Error::NoCid)
+        }
+
+        /// Get the CID of an `ipfs://` URI, dropping any path inside it
+        fn cid_from_uri(uri: &str) -> Option<String> {
+            let cid = uri.strip_prefix(IPFS_SCHEME)?.split('/').next()?;
+            Self::ensure_cid(cid).ok()?;
+            Some(String::from(cid))
+        }
+
+        /// Derive the chunk a provider must prove for a pool in a period
+        fn challenge_index(&self, pool: &Pool, provider: AccountId, period: u32) -> u32 {
+            let mut seed = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_encoded::<Blake2x256, _>(&(pool.chunk_root, provider, period), &mut seed);
+            let [a, b, c, d, ..] = seed;
+            u32::from_le_bytes([a, b, c, d]).checked_rem(pool.chunk_count).unwrap_or(0)
+        }
+
+        /// Check that `chunk` is chunk `index` of the pool's file. The proof
+        /// must be exactly as deep as the tree, so inner nodes can't pass as chunks.
+        fn verify_chunk(pool: &Pool, index: u32, chunk: &[u8], proof: &[Hash]) -> bool {
+            let depth = match pool.chunk_count.checked_sub(1) {
+                Some(0) | None => 0,
+                Some(last) => u32::BITS.saturating_sub(last.leading_zeros()),
+            };
+            if chunk.len() > CHUNK_SIZE || proof.len() != depth as usize {
+                return false;
+            }
+            let mut node = Self::hash(chunk);
+            let mut position = index;
+            for sibling in proof {
+                let mut pair = Vec::with_capacity(64);
+                if position & 1 == 0 {
+                    pair.extend_from_slice(node.as_ref());
+                    pair.extend_from_slice(sibling.as_ref());
+                } else {
+                    pair.extend_from_slice(sibling.as_ref());
+                    pair.extend_from_slice(node.as_ref());
+                }
+                node = Self::hash(&pair);
+                position >>= 1;
+            }
+            node == pool.chunk_root
+        }
+
+        /// Blake2x256 hash of `bytes`
+        fn hash(bytes: &[u8]) -> Hash {
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(bytes, &mut output);
+            Hash::from(output)
+        }
+    }
+
+    /// Unit tests. The off-chain environment can't call the collection, so
+    /// `bond_token`'s URI parsing is tested on its own.
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        const CID: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+        fn new_registry(period_length: BlockNumber) -> PinningBonds {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            test::set_callee::<DefaultEnvironment>(accounts.django);
+            test::set_account_balance::<DefaultEnvironment>(accounts.django, 1_000);
+            PinningBonds::new(accounts.frank, period_length)
+        }
+
+        fn advance_blocks(count: u32) {
+            for _ in 0..count {
+                test::advance_block::<DefaultEnvironment>();
+            }
+        }
+
+        fn pair(left: Hash, right: Hash) -> Hash {
+            PinningBonds::hash(&[left.as_ref(), right.as_ref()].concat())
+        }
+
+        /// Three chunks, the last paired with itself
+        fn chunks() -> (Vec<Vec<u8>>, Vec<Hash>, Hash) {
+            let chunks = vec![vec![1; CHUNK_SIZE], vec![2; CHUNK_SIZE], vec![3; 10]];
+            let leaves: Vec<Hash> = chunks.iter().map(|chunk| PinningBonds::hash(chunk)).collect();
+            let left = pair(leaves[0], leaves[1]);
+            let right = pair(leaves[2], leaves[2]);
+            (chunks, vec![left, right], pair(left, right))
+        }
+
+        fn proof_for(index: u32) -> (Vec<u8>, Vec<Hash>) {
+            let (chunks, branches, _) = chunks();
+            let leaves: Vec<Hash> = chunks.iter().map(|chunk| PinningBonds::hash(chunk)).collect();
+            let (sibling, branch) = match index {
+                0 => (leaves[1], branches[1]),
+                1 => (leaves[0], branches[1]),
+                _ => (leaves[2], branches[0]),
+            };
+            (chunks[index as usize].clone(), vec![sibling, branch])
+        }
+
+        #[ink::test]
+        fn providers_earn_rewards_for_proven_periods() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut registry = new_registry(10);
+            let (_, _, root) = chunks();
+            let cid = String::from(CID);
+            test::set_value_transferred::<DefaultEnvironment>(250);
+            assert_eq!(registry.open_pool(cid.clone(), root, 0, 100), Err(Error::InvalidPool));
+            assert_eq!(registry.open_pool(cid.clone(), root, 3, 100), Ok(()));
+            assert_eq!(registry.open_pool(cid.clone(), root, 3, 100), Err(Error::PoolExists));
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let index = registry.challenge(cid.clone(), accounts.bob).unwrap();
+            let (chunk, proof) = proof_for(index);
+            let unregistered = registry.submit_proof(cid.clone(), chunk.clone(), proof.clone());
+            assert_eq!(unregistered, Err(Error::NotProvider));
+            assert_eq!(registry.set_provider(accounts.bob, true), Err(Error::NotAdmin));
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(registry.set_provider(accounts.bob, true), Ok(()));
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let (wrong_chunk, _) = proof_for((index + 1) % 3);
+            let wrong = registry.submit_proof(cid.clone(), wrong_chunk, proof.clone());
+            assert_eq!(wrong, Err(Error::InvalidProof));
+            assert_eq!(registry.submit_proof(cid.clone(), chunk.clone(), proof.clone()), Ok(()));
+            assert_eq!(registry.submit_proof(cid.clone(), chunk, proof), Err(Error::AlreadyProven));
+
+            // Each period asks again
+            for _ in 0..2 {
+                advance_blocks(10);
+                let (chunk, proof) = proof_for(registry.challenge(cid.clone(), accounts.bob).unwrap());
+                assert_eq!(registry.submit_proof(cid.clone(), chunk, proof), Ok(()));
+            }
+            assert_eq!(registry.record_of(cid.clone(), accounts.bob).unwrap().unclaimed, 3);
+
+            // The pool covers two of the three periods; the third waits for more bonds
+            let balance = test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap();
+            assert_eq!(registry.claim(cid.clone()), Ok(200));
+            assert_eq!(test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap(), balance + 200);
+            assert_eq!(registry.claim(cid.clone()), Ok(0));
+            test::set_value_transferred::<DefaultEnvironment>(100);
+            assert_eq!(registry.bond(cid.clone()), Ok(()));
+            assert_eq!(registry.claim(cid.clone()), Ok(100));
+            assert_eq!(registry.pool(cid).unwrap().balance, 50);
+        }
+
+        #[ink::test]
+        fn proofs_must_be_as_deep_as_the_tree() {
+            let (chunks, branches, root) = chunks();
+            let pool = Pool {
+                chunk_root: root,
+                chunk_count: 3,
+                reward_per_period: 1,
+                balance: 1,
+            };
+            let (chunk, proof) = proof_for(2);
+            assert!(PinningBonds::verify_chunk(&pool, 2, &chunk, &proof));
+            assert!(!PinningBonds::verify_chunk(&pool, 0, &chunk, &proof));
+
+            // An inner node posing as a chunk is one level short
+            let leaves = [PinningBonds::hash(&chunks[0]), PinningBonds::hash(&chunks[1])];
+            let inner = [leaves[0].as_ref(), leaves[1].as_ref()].concat();
+            assert_eq!(pair(PinningBonds::hash(&inner), branches[1]), root);
+            assert!(!PinningBonds::verify_chunk(&pool, 0, &inner, &branches[1..]));
+
+            let single = Pool {
+                chunk_root: PinningBonds::hash(&chunks[2]),
+                chunk_count: 1,
+                ..pool
+            };
+            assert!(PinningBonds::verify_chunk(&single, 0, &chunks[2], &[]));
+            assert!(!PinningBonds::verify_chunk(&single, 0, &[0; CHUNK_SIZE + 1], &[]));
+        }
+
+        #[ink::test]
+        fn token_uris_give_their_cid() {
+            let model = PinningBonds::cid_from_uri("ipfs://bafyModel/scene.glb");
+            assert_eq!(model, Some(String::from("bafyModel")));
+            assert_eq!(PinningBonds::cid_from_uri(&format!("ipfs://{CID}")), Some(String::from(CID)));
+            assert_eq!(PinningBonds::cid_from_uri("https://example.com/model.glb"), None);
+            assert_eq!(PinningBonds::cid_from_uri("ipfs://"), None);
+        }
+    }
+}
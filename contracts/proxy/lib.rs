@@ -0,0 +1,147 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::indexing_slicing
+    )
+)]
+
+/// VR Genesis Frame - Delegating Proxy
+/// Holds a collection's storage and runs every call it doesn't recognise
+/// against a registered implementation code hash via `delegate_call`, so the
+/// logic can be upgraded while the address and state stay put.
+///
+/// Implementations must be written for this: ink! decodes the storage root
+/// (key 0) before every message, so an implementation may only keep `Lazy`
+/// and `Mapping` fields in its root and must not use `PROXY_STATE_KEY`.
+/// Messages below use fixed selectors in the `0x5052xxxx` range; an
+/// implementation message with the same selector would be shadowed.
+#[ink::contract]
+pub mod proxy {
+    use ink::env::CallFlags;
+    use ink::storage::{traits::ManualKey, Lazy};
+    use scale::{Decode, Encode};
+
+    /// Storage key of the proxy's own state ("PRXY"), clear of key 0
+    pub const PROXY_STATE_KEY: u32 = 0x5052_5859;
+
+    /// Event emitted when the admin points the proxy at new logic
+    #[ink(event)]
+    pub struct ImplementationChanged {
+        #[ink(topic)]
+        old: Hash,
+        #[ink(topic)]
+        new: Hash,
+    }
+
+    /// Errors returned by the proxy's own messages
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Caller is not the proxy admin
+        NotAdmin,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Where calls are delegated and who may change it
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct ProxyState {
+        pub implementation: Hash,
+        pub admin: AccountId,
+    }
+
+    #[ink(storage)]
+    pub struct Proxy {
+        /// Kept under a manual key so the root stays free for the implementation
+        state: Lazy<ProxyState, ManualKey<PROXY_STATE_KEY>>,
+    }
+
+    impl Proxy {
+        /// Create a proxy delegating to `implementation`, administered by the caller
+        #[ink(constructor)]
+        pub fn new(implementation: Hash) -> Self {
+            let mut state = Lazy::new();
+            state.set(&ProxyState { implementation, admin: Self::env().caller() });
+            Self { state }
+        }
+
+        /// Get the code hash calls are delegated to
+        #[ink(message, selector = 0x5052_0001)]
+        pub fn implementation(&self) -> Hash {
+            self.state().implementation
+        }
+
+        /// Get the account allowed to change the implementation
+        #[ink(message, selector = 0x5052_0002)]
+        pub fn proxy_admin(&self) -> AccountId {
+            self.state().admin
+        }
+
+        /// Delegate future calls to `implementation` (admin only).
+        /// The new code must read the existing storage layout.
+        #[ink(message, selector = 0x5052_0003)]
+        pub fn set_implementation(&mut self, implementation: Hash) -> Result<()> {
+            let mut state = self.state();
+            if self.env().caller() != state.admin {
+                return Err(Error::NotAdmin);
+            }
+
+            let old = state.implementation;
+            state.implementation = implementation;
+            self.state.set(&state);
+
+            self.env().emit_event(ImplementationChanged { old, new: implementation });
+            Ok(())
+        }
+
+        /// Run any other call against the implementation with the proxy's
+        /// storage, value and input, returning its output unchanged
+        #[ink(message, payable, selector = _)]
+        pub fn forward(&self) {
+            ink::env::call::build_call::<ink::env::DefaultEnvironment>()
+                .delegate(self.state().implementation)
+                .call_flags(CallFlags::default().set_forward_input(true).set_tail_call(true))
+                .invoke();
+            unreachable!("tail calls don't return");
+        }
+
+        /// Read the proxy state, written once by the constructor
+        fn state(&self) -> ProxyState {
+            self.state.get().unwrap_or(ProxyState {
+                implementation: Hash::default(),
+                admin: AccountId::from([0u8; 32]),
+            })
+        }
+    }
+
+    /// Unit tests
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        #[ink::test]
+        fn only_admin_changes_implementation() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let v1 = Hash::from([1u8; 32]);
+            let v2 = Hash::from([2u8; 32]);
+            let mut proxy = Proxy::new(v1);
+            assert_eq!(proxy.implementation(), v1);
+            assert_eq!(proxy.proxy_admin(), accounts.alice);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(proxy.set_implementation(v2), Err(Error::NotAdmin));
+            assert_eq!(proxy.implementation(), v1);
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(proxy.set_implementation(v2), Ok(()));
+            assert_eq!(proxy.implementation(), v2);
+            assert_eq!(test::recorded_events().count(), 1);
+        }
+    }
+}
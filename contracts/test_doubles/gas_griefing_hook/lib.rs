@@ -0,0 +1,64 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::indexing_slicing
+    )
+)]
+
+/// Test double - Gas Griefing Hook
+/// A transfer hook that burns all the gas it is given, before a transfer,
+/// after it, or both. Tests register it to check the collection's per-hook
+/// gas limit: a griefing `before_transfer` only rejects the transfer, and a
+/// griefing `after_transfer` can't undo one. Never deploy it outside tests.
+#[ink::contract]
+pub mod gas_griefing_hook {
+    use ink::env::hash::Blake2x256;
+    use polka_space_nft_core::TransferHook;
+
+    #[ink(storage)]
+    pub struct GasGriefingHook {
+        /// Burn gas in `before_transfer`
+        grief_before: bool,
+        /// Burn gas in `after_transfer`
+        grief_after: bool,
+    }
+
+    impl GasGriefingHook {
+        /// Create a hook griefing the calls selected
+        #[ink(constructor)]
+        pub fn new(grief_before: bool, grief_after: bool) -> Self {
+            Self { grief_before, grief_after }
+        }
+
+        /// Hash forever, so the call only ends when its gas runs out
+        fn burn_gas(&self) -> ! {
+            let mut digest = [0u8; 32];
+            loop {
+                digest = self.env().hash_bytes::<Blake2x256>(&digest);
+            }
+        }
+    }
+
+    impl TransferHook for GasGriefingHook {
+        /// Approve every transfer, unless griefing first
+        #[ink(message)]
+        fn before_transfer(&mut self, _from: Option<AccountId>, _to: Option<AccountId>, _token_id: u32) -> bool {
+            if self.grief_before {
+                self.burn_gas();
+            }
+            true
+        }
+
+        /// Grief the notification, if asked to
+        #[ink(message)]
+        fn after_transfer(&mut self, _from: Option<AccountId>, _to: Option<AccountId>, _token_id: u32) {
+            if self.grief_after {
+                self.burn_gas();
+            }
+        }
+    }
+}
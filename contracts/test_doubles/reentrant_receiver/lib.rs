@@ -0,0 +1,92 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::indexing_slicing
+    )
+)]
+
+/// Test double - Reentrant Receiver
+/// A transfer hook that calls back into the collection that notified it,
+/// trying to buy the token being moved while the first sale is still
+/// settling. It records how the collection answered so tests can check the
+/// second purchase never went through. Never deploy it outside tests.
+#[ink::contract]
+pub mod reentrant_receiver {
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use polka_space_nft_core::TransferHook;
+    use scale::{Decode, Encode};
+
+    /// How the collection answered the call back into it
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum Reentry {
+        /// No transfer has been hooked yet
+        NotAttempted,
+        /// The runtime refused or trapped the call
+        Denied,
+        /// The collection returned the error with this variant index
+        Rejected(u8),
+        /// The collection accepted the reentrant call
+        Succeeded,
+    }
+
+    #[ink(storage)]
+    pub struct ReentrantReceiver {
+        /// Collection the receiver attacks when it calls the hook
+        collection: AccountId,
+        /// Outcome of the latest reentrant call
+        last_reentry: Reentry,
+    }
+
+    impl ReentrantReceiver {
+        /// Create a receiver that re-enters `collection`
+        #[ink(constructor)]
+        pub fn new(collection: AccountId) -> Self {
+            Self {
+                collection,
+                last_reentry: Reentry::NotAttempted,
+            }
+        }
+
+        /// Get how the collection answered the latest reentrant call
+        #[ink(message)]
+        pub fn last_reentry(&self) -> Reentry {
+            self.last_reentry
+        }
+
+        /// Call the collection's `buy` for `token_id` from inside its own call
+        fn reenter(&mut self, token_id: u32) {
+            let result = build_call::<Environment>()
+                .call(self.collection)
+                // The runtime denies this unless the collection let its hooks re-enter it
+                .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!("buy"))).push_arg(token_id))
+                // Only the variant index of the collection's error is read
+                .returns::<core::result::Result<(), u8>>()
+                .try_invoke();
+            self.last_reentry = match result {
+                Ok(Ok(Ok(()))) => Reentry::Succeeded,
+                Ok(Ok(Err(index))) => Reentry::Rejected(index),
+                _ => Reentry::Denied,
+            };
+        }
+    }
+
+    impl TransferHook for ReentrantReceiver {
+        /// Try to buy the token again before the collection moves it
+        #[ink(message)]
+        fn before_transfer(&mut self, _from: Option<AccountId>, _to: Option<AccountId>, token_id: u32) -> bool {
+            if self.env().caller() == self.collection {
+                self.reenter(token_id);
+            }
+            true
+        }
+
+        /// Nothing to do once a transfer went through
+        #[ink(message)]
+        fn after_transfer(&mut self, _from: Option<AccountId>, _to: Option<AccountId>, _token_id: u32) {}
+    }
+}
@@ -0,0 +1,102 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::indexing_slicing
+    )
+)]
+
+/// Test double - Reverting Buyer
+/// A contract that buys and makes offers on a collection, then traps when
+/// the token is about to reach it. Registered as a transfer hook, it stands
+/// in for a buyer that can't take delivery, so tests can check a sale to it
+/// is undone whole: the token, listing, escrow and payment all stay where
+/// they were. Never deploy it outside tests.
+#[ink::contract]
+pub mod reverting_buyer {
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::CallFlags;
+    use polka_space_nft_core::TransferHook;
+
+    #[ink(storage)]
+    pub struct RevertingBuyer {
+        /// Collection the buyer trades on
+        collection: AccountId,
+    }
+
+    impl RevertingBuyer {
+        /// Create a buyer trading on `collection`
+        #[ink(constructor)]
+        pub fn new(collection: AccountId) -> Self {
+            Self { collection }
+        }
+
+        /// Buy a listed token, paying with the value sent. Returns the
+        /// variant index of the collection's error if it refused, or
+        /// `u8::MAX` if the call failed outright.
+        #[ink(message, payable)]
+        pub fn buy(&mut self, token_id: u32) -> Option<u8> {
+            let result = build_call::<Environment>()
+                .call(self.collection)
+                .call_flags(Self::hook_reentry())
+                .transferred_value(self.env().transferred_value())
+                .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!("buy"))).push_arg(token_id))
+                .returns::<core::result::Result<(), u8>>()
+                .try_invoke();
+            match result {
+                Ok(Ok(Ok(()))) => None,
+                Ok(Ok(Err(index))) => Some(index),
+                _ => Some(u8::MAX),
+            }
+        }
+
+        /// Escrow the value sent as an offer for a token
+        #[ink(message, payable)]
+        pub fn make_offer(&mut self, token_id: u32) -> bool {
+            self.call_collection(ink::selector_bytes!("make_offer"), token_id, self.env().transferred_value())
+        }
+
+        /// Withdraw an offer, getting the escrow back
+        #[ink(message)]
+        pub fn withdraw_offer(&mut self, token_id: u32) -> bool {
+            self.call_collection(ink::selector_bytes!("withdraw_offer"), token_id, 0)
+        }
+
+        /// Call one of the collection's `bool` messages taking a token ID
+        fn call_collection(&self, selector: [u8; 4], token_id: u32, value: Balance) -> bool {
+            let result = build_call::<Environment>()
+                .call(self.collection)
+                .call_flags(Self::hook_reentry())
+                .transferred_value(value)
+                .exec_input(ExecutionInput::new(Selector::new(selector)).push_arg(token_id))
+                .returns::<bool>()
+                .try_invoke();
+            matches!(result, Ok(Ok(true)))
+        }
+
+        /// Let the collection call back into the buyer as a transfer hook,
+        /// so a sale to it reaches `before_transfer` and traps there rather
+        /// than being denied by the runtime
+        fn hook_reentry() -> CallFlags {
+            CallFlags::default().set_allow_reentry(true)
+        }
+    }
+
+    impl TransferHook for RevertingBuyer {
+        /// Trap instead of taking delivery of a token
+        #[ink(message)]
+        fn before_transfer(&mut self, _from: Option<AccountId>, to: Option<AccountId>, token_id: u32) -> bool {
+            if to == Some(self.env().account_id()) {
+                panic!("refusing delivery of token {}", token_id);
+            }
+            true
+        }
+
+        /// Nothing to do once a transfer went through
+        #[ink(message)]
+        fn after_transfer(&mut self, _from: Option<AccountId>, _to: Option<AccountId>, _token_id: u32) {}
+    }
+}
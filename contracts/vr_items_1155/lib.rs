@@ -0,0 +1,576 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::indexing_slicing
+    )
+)]
+
+/// VR Genesis Frame - Stackable VR Items
+/// PSP37-style multi-token contract for props that come in quantities, such
+/// as a hundred identical wooden chairs. Anyone can create an item type and
+/// becomes its creator, the only account that can mint it, up to an optional
+/// supply cap. Balances, allowances and transfers work per item ID, with
+/// batch variants moving several IDs at once. Item URIs come from a template
+/// where `{id}` stands for the item ID as 64 lowercase hex digits, as in
+/// ERC-1155, unless the creator sets one for the item.
+#[ink::contract]
+pub mod vr_items_1155 {
+    use ink::prelude::{string::String, vec::Vec};
+    use ink::storage::Mapping;
+    use polka_space_nft_core::is_valid_recipient;
+    use scale::{Decode, Encode};
+
+    /// Maximum number of item IDs in one batch call
+    pub const MAX_BATCH_SIZE: usize = 100;
+
+    /// Maximum length of a URI or URI template in bytes
+    pub const MAX_URI_LEN: usize = 256;
+
+    /// Placeholder a URI template's item ID replaces
+    pub const ID_PLACEHOLDER: &str = "{id}";
+
+    /// An item type
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Item {
+        /// Account that created the item and may mint it
+        pub creator: AccountId,
+        /// Most units that can exist at once, if capped
+        pub max_supply: Option<Balance>,
+    }
+
+    /// Event emitted when an item type is created
+    #[ink(event)]
+    pub struct ItemCreated {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        creator: AccountId,
+        max_supply: Option<Balance>,
+    }
+
+    /// Event emitted when units of an item are minted, transferred or burned
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        #[ink(topic)]
+        id: u32,
+        value: Balance,
+    }
+
+    /// Event emitted once for a batch mint or transfer instead of one
+    /// `Transfer` per item ID
+    #[ink(event)]
+    pub struct TransferBatch {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        ids_amounts: Vec<(u32, Balance)>,
+    }
+
+    /// Event emitted when an owner sets an operator's allowance
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        /// Item the allowance is for; `None` for all items
+        id: Option<u32>,
+        value: Balance,
+    }
+
+    /// Errors returned by the items contract
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Caller is not the contract admin
+        NotAdmin,
+        /// Item type does not exist
+        ItemNotFound,
+        /// Caller is not the item's creator
+        NotCreator,
+        /// Sender holds fewer units than requested
+        InsufficientBalance,
+        /// Caller's allowance from the owner is too small
+        NotAllowed,
+        /// Minting would exceed the item's supply cap
+        SupplyExceeded,
+        /// Recipient is the zero address or this contract
+        InvalidRecipient,
+        /// Accounts can't be their own operators
+        SelfApprove,
+        /// Batch is empty or larger than `MAX_BATCH_SIZE`
+        InvalidBatch,
+        /// URI exceeds `MAX_URI_LEN`
+        InputTooLong,
+        /// A counter or balance would overflow
+        Overflow,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Allowance key: (owner, operator, item)
+    type AllowanceKey = (AccountId, AccountId, u32);
+
+    #[ink(storage)]
+    pub struct VrItems {
+        /// Account that sets the URI template
+        admin: AccountId,
+        /// URI of every item without its own, with `{id}` for the item ID
+        uri_template: String,
+        items: Mapping<u32, Item>,
+        item_count: u32,
+        /// URIs creators set for their items
+        item_uris: Mapping<u32, String>,
+        /// Units of each item in existence
+        supply: Mapping<u32, Balance>,
+        /// Units of each item each account holds, keyed by (account, item)
+        balances: Mapping<(AccountId, u32), Balance>,
+        /// Units operators may move per item, keyed by (owner, operator, item)
+        allowances: Mapping<AllowanceKey, Balance>,
+        /// Operators allowed to move all of an owner's items
+        operators: Mapping<(AccountId, AccountId), ()>,
+    }
+
+    impl VrItems {
+        /// Create an item collection administered by the caller
+        #[ink(constructor)]
+        pub fn new(uri_template: String) -> Self {
+            Self {
+                admin: Self::env().caller(),
+                uri_template,
+                items: Mapping::default(),
+                item_count: 0,
+                item_uris: Mapping::default(),
+                supply: Mapping::default(),
+                balances: Mapping::default(),
+                allowances: Mapping::default(),
+                operators: Mapping::default(),
+            }
+        }
+
+        /// Get the admin account
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// Get the number of item types created
+        #[ink(message)]
+        pub fn item_count(&self) -> u32 {
+            self.item_count
+        }
+
+        /// Get an item type
+        #[ink(message)]
+        pub fn item(&self, id: u32) -> Option<Item> {
+            self.items.get(id)
+        }
+
+        /// Get the units of an item an account holds, or of all items if `id` is `None`
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId, id: Option<u32>) -> Balance {
+            match id {
+                Some(id) => self.balances.get((owner, id)).unwrap_or(0),
+                None => (0..self.item_count)
+                    .fold(0, |total: Balance, id| total.saturating_add(self.balance_of(owner, Some(id)))),
+            }
+        }
+
+        /// Get the balances of several accounts and items at once
+        #[ink(message)]
+        pub fn balance_of_batch(&self, queries: Vec<(AccountId, u32)>) -> Vec<Balance> {
+            queries
+                .into_iter()
+                .take(MAX_BATCH_SIZE)
+                .map(|(owner, id)| self.balance_of(owner, Some(id)))
+                .collect()
+        }
+
+        /// Get the units of an item in existence, or the number of item types if `id` is `None`
+        #[ink(message)]
+        pub fn total_supply(&self, id: Option<u32>) -> Balance {
+            match id {
+                Some(id) => self.supply.get(id).unwrap_or(0),
+                None => Balance::from(self.item_count),
+            }
+        }
+
+        /// Get how many units of an item an operator may move for an owner;
+        /// with `id` of `None`, whether it may move all of them
+        #[ink(message)]
+        pub fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<u32>) -> Balance {
+            if self.operators.contains((owner, operator)) {
+                return Balance::MAX;
+            }
+            id.and_then(|id| self.allowances.get((owner, operator, id)))
+                .unwrap_or(0)
+        }
+
+        /// Let an operator move `value` units of an item for the caller, or
+        /// with `id` of `None` all their items (any nonzero `value`) or none
+        #[ink(message)]
+        pub fn approve(&mut self, operator: AccountId, id: Option<u32>, value: Balance) -> Result<()> {
+            let owner = self.env().caller();
+            if operator == owner {
+                return Err(Error::SelfApprove);
+            }
+            match id {
+                Some(id) if value == 0 => self.allowances.remove((owner, operator, id)),
+                Some(id) => {
+                    self.allowances.insert((owner, operator, id), &value);
+                }
+                None if value == 0 => self.operators.remove((owner, operator)),
+                None => {
+                    self.operators.insert((owner, operator), &());
+                }
+            }
+            self.env().emit_event(Approval {
+                owner,
+                operator,
+                id,
+                value,
+            });
+            Ok(())
+        }
+
+        /// Get an item's URI: its own if its creator set one, else the
+        /// template with the item ID filled in
+        #[ink(message)]
+        pub fn uri(&self, id: u32) -> Option<String> {
+            if !self.items.contains(id) {
+                return None;
+            }
+            Some(self.item_uris.get(id).unwrap_or_else(|| {
+                self.uri_template
+                    .replace(ID_PLACEHOLDER, &ink::prelude::format!("{:064x}", id))
+            }))
+        }
+
+        /// Replace the URI template (admin only)
+        #[ink(message)]
+        pub fn set_uri_template(&mut self, uri_template: String) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAdmin);
+            }
+            if uri_template.len() > MAX_URI_LEN {
+                return Err(Error::InputTooLong);
+            }
+            self.uri_template = uri_template;
+            Ok(())
+        }
+
+        /// Give an item its own URI instead of the template's, or go back
+        /// to the template with an empty one (item creator only)
+        #[ink(message)]
+        pub fn set_item_uri(&mut self, id: u32, uri: String) -> Result<()> {
+            self.ensure_creator(id)?;
+            if uri.len() > MAX_URI_LEN {
+                return Err(Error::InputTooLong);
+            }
+            if uri.is_empty() {
+                self.item_uris.remove(id);
+            } else {
+                self.item_uris.insert(id, &uri);
+            }
+            Ok(())
+        }
+
+        /// Create an item type the caller can mint, capped at `max_supply`
+        /// units if given
+        #[ink(message)]
+        pub fn create_item(&mut self, max_supply: Option<Balance>) -> Result<u32> {
+            let creator = self.env().caller();
+            let id = self.item_count;
+            self.item_count = id.checked_add(1).ok_or(Error::Overflow)?;
+            self.items.insert(id, &Item { creator, max_supply });
+
+            self.env().emit_event(ItemCreated {
+                id,
+                creator,
+                max_supply,
+            });
+            Ok(id)
+        }
+
+        /// Mint units of one or more items to `to` (each item's creator only)
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, ids_amounts: Vec<(u32, Balance)>) -> Result<()> {
+            Self::ensure_batch(&ids_amounts)?;
+            self.ensure_valid_recipient(to)?;
+            for &(id, value) in &ids_amounts {
+                let item = self.ensure_creator(id)?;
+                let supply = self.total_supply(Some(id)).checked_add(value).ok_or(Error::Overflow)?;
+                if item.max_supply.is_some_and(|max| supply > max) {
+                    return Err(Error::SupplyExceeded);
+                }
+                self.supply.insert(id, &supply);
+                self.credit(to, id, value)?;
+            }
+            self.emit_transfer(None, Some(to), ids_amounts);
+            Ok(())
+        }
+
+        /// Destroy units of one or more items the caller holds
+        #[ink(message)]
+        pub fn burn(&mut self, ids_amounts: Vec<(u32, Balance)>) -> Result<()> {
+            Self::ensure_batch(&ids_amounts)?;
+            let owner = self.env().caller();
+            for &(id, value) in &ids_amounts {
+                self.debit(owner, id, value)?;
+                let supply = self.total_supply(Some(id)).saturating_sub(value);
+                self.supply.insert(id, &supply);
+            }
+            self.emit_transfer(Some(owner), None, ids_amounts);
+            Ok(())
+        }
+
+        /// Send units of an item the caller holds. `data` is accepted for
+        /// PSP37 compatibility and ignored.
+        #[ink(message)]
+        pub fn transfer(&mut self, to: AccountId, id: u32, value: Balance, data: Vec<u8>) -> Result<()> {
+            let owner = self.env().caller();
+            self.transfer_from(owner, to, id, value, data)
+        }
+
+        /// Send units of an item from `from`, spending the caller's allowance
+        /// unless the caller is `from`
+        #[ink(message)]
+        pub fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: u32,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<()> {
+            self.batch_transfer_from(from, to, ink::prelude::vec![(id, value)], data)
+        }
+
+        /// Send units of several items the caller holds in one call
+        #[ink(message)]
+        pub fn batch_transfer(
+            &mut self,
+            to: AccountId,
+            ids_amounts: Vec<(u32, Balance)>,
+            data: Vec<u8>,
+        ) -> Result<()> {
+            let owner = self.env().caller();
+            self.batch_transfer_from(owner, to, ids_amounts, data)
+        }
+
+        /// Send units of several items from `from` in one call, spending the
+        /// caller's allowances unless the caller is `from`
+        #[ink(message)]
+        pub fn batch_transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            ids_amounts: Vec<(u32, Balance)>,
+            _data: Vec<u8>,
+        ) -> Result<()> {
+            Self::ensure_batch(&ids_amounts)?;
+            self.ensure_valid_recipient(to)?;
+            let operator = self.env().caller();
+            for &(id, value) in &ids_amounts {
+                if operator != from {
+                    self.spend_allowance(from, operator, id, value)?;
+                }
+                self.debit(from, id, value)?;
+                self.credit(to, id, value)?;
+            }
+            self.emit_transfer(Some(from), Some(to), ids_amounts);
+            Ok(())
+        }
+
+        /// Emit `Transfer` for a single item, `TransferBatch` for several
+        fn emit_transfer(
+            &self,
+            from: Option<AccountId>,
+            to: Option<AccountId>,
+            ids_amounts: Vec<(u32, Balance)>,
+        ) {
+            match ids_amounts.as_slice() {
+                &[(id, value)] => self.env().emit_event(Transfer { from, to, id, value }),
+                _ => self.env().emit_event(TransferBatch { from, to, ids_amounts }),
+            }
+        }
+
+        /// Use up part of an operator's allowance for an item
+        fn spend_allowance(
+            &mut self,
+            owner: AccountId,
+            operator: AccountId,
+            id: u32,
+            value: Balance,
+        ) -> Result<()> {
+            if self.operators.contains((owner, operator)) {
+                return Ok(());
+            }
+            let allowance = self.allowance(owner, operator, Some(id));
+            let left = allowance.checked_sub(value).ok_or(Error::NotAllowed)?;
+            self.allowances.insert((owner, operator, id), &left);
+            Ok(())
+        }
+
+        /// Add units of an item to an account's balance
+        fn credit(&mut self, to: AccountId, id: u32, value: Balance) -> Result<()> {
+            let balance = self.balance_of(to, Some(id)).checked_add(value).ok_or(Error::Overflow)?;
+            self.balances.insert((to, id), &balance);
+            Ok(())
+        }
+
+        /// Take units of an item from an account's balance
+        fn debit(&mut self, from: AccountId, id: u32, value: Balance) -> Result<()> {
+            if !self.items.contains(id) {
+                return Err(Error::ItemNotFound);
+            }
+            let balance = self
+                .balance_of(from, Some(id))
+                .checked_sub(value)
+                .ok_or(Error::InsufficientBalance)?;
+            if balance == 0 {
+                self.balances.remove((from, id));
+            } else {
+                self.balances.insert((from, id), &balance);
+            }
+            Ok(())
+        }
+
+        /// Get an item the caller created
+        fn ensure_creator(&self, id: u32) -> Result<Item> {
+            let item = self.items.get(id).ok_or(Error::ItemNotFound)?;
+            if item.creator != self.env().caller() {
+                return Err(Error::NotCreator);
+            }
+            Ok(item)
+        }
+
+        /// Check that `to` can hold items
+        fn ensure_valid_recipient(&self, to: AccountId) -> Result<()> {
+            if !is_valid_recipient(&to, &self.env().account_id()) {
+                return Err(Error::InvalidRecipient);
+            }
+            Ok(())
+        }
+
+        /// Check that a batch names between one and `MAX_BATCH_SIZE` items
+        fn ensure_batch(ids_amounts: &[(u32, Balance)]) -> Result<()> {
+            if ids_amounts.is_empty() || ids_amounts.len() > MAX_BATCH_SIZE {
+                return Err(Error::InvalidBatch);
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        fn new_items() -> VrItems {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            test::set_callee::<DefaultEnvironment>(accounts.django);
+            VrItems::new(String::from("ipfs://QmItems/{id}.json"))
+        }
+
+        #[ink::test]
+        fn creators_mint_up_to_the_supply_cap() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut items = new_items();
+            let chair = items.create_item(Some(100)).unwrap();
+            let lamp = items.create_item(None).unwrap();
+            assert_eq!(items.total_supply(None), 2);
+
+            assert_eq!(items.mint(accounts.bob, vec![(chair, 60), (lamp, 5)]), Ok(()));
+            assert_eq!(items.mint(accounts.bob, vec![(chair, 41)]), Err(Error::SupplyExceeded));
+            assert_eq!(items.mint(accounts.django, vec![(chair, 1)]), Err(Error::InvalidRecipient));
+            assert_eq!(items.mint(accounts.bob, Vec::new()), Err(Error::InvalidBatch));
+            assert_eq!(items.balance_of(accounts.bob, Some(chair)), 60);
+            assert_eq!(items.balance_of(accounts.bob, None), 65);
+            assert_eq!(items.total_supply(Some(chair)), 60);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(items.mint(accounts.bob, vec![(chair, 1)]), Err(Error::NotCreator));
+            assert_eq!(items.burn(vec![(chair, 10)]), Ok(()));
+            assert_eq!(items.total_supply(Some(chair)), 50);
+            assert_eq!(items.burn(vec![(lamp, 6)]), Err(Error::InsufficientBalance));
+        }
+
+        #[ink::test]
+        fn operators_move_items_within_their_allowance() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut items = new_items();
+            let chair = items.create_item(None).unwrap();
+            let lamp = items.create_item(None).unwrap();
+            items.mint(accounts.bob, vec![(chair, 10), (lamp, 10)]).unwrap();
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(items.transfer(accounts.charlie, chair, 3, Vec::new()), Ok(()));
+            assert_eq!(items.approve(accounts.bob, None, 1), Err(Error::SelfApprove));
+            assert_eq!(items.approve(accounts.eve, Some(chair), 4), Ok(()));
+
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                items.transfer_from(accounts.bob, accounts.eve, lamp, 1, Vec::new()),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(items.transfer_from(accounts.bob, accounts.eve, chair, 4, Vec::new()), Ok(()));
+            assert_eq!(items.allowance(accounts.bob, accounts.eve, Some(chair)), 0);
+
+            // Approving all items covers every ID without limit
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            items.approve(accounts.eve, None, 1).unwrap();
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                items.batch_transfer_from(
+                    accounts.bob,
+                    accounts.eve,
+                    vec![(chair, 3), (lamp, 10)],
+                    Vec::new()
+                ),
+                Ok(())
+            );
+            assert_eq!(items.balance_of(accounts.bob, None), 0);
+            assert_eq!(
+                items.balance_of_batch(vec![
+                    (accounts.eve, chair),
+                    (accounts.eve, lamp),
+                    (accounts.charlie, chair)
+                ]),
+                vec![7, 10, 3]
+            );
+        }
+
+        #[ink::test]
+        fn uris_fill_in_the_template() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut items = new_items();
+            let chair = items.create_item(None).unwrap();
+            let lamp = items.create_item(None).unwrap();
+            assert_eq!(
+                items.uri(lamp),
+                Some(String::from(
+                    "ipfs://QmItems/0000000000000000000000000000000000000000000000000000000000000001.json"
+                ))
+            );
+            assert_eq!(items.uri(7), None);
+
+            assert_eq!(items.set_item_uri(chair, String::from("ipfs://QmChair")), Ok(()));
+            assert_eq!(items.uri(chair), Some(String::from("ipfs://QmChair")));
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(items.set_item_uri(lamp, String::from("ipfs://QmLamp")), Err(Error::NotCreator));
+            assert_eq!(items.set_uri_template(String::new()), Err(Error::NotAdmin));
+        }
+    }
+}
@@ -0,0 +1,3212 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::arithmetic_side_effects,
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::indexing_slicing
+    )
+)]
+
+/// VR Genesis Frame - Land Parcel Contract
+/// Each token is a parcel on a world grid, identified by its (x, y, world_id)
+/// coordinate. A coordinate can only ever be claimed by a single parcel.
+/// Adjacent parcels can be merged into an estate token and split back later,
+/// and prop NFTs can be anchored into a parcel's scene by its owner or builders.
+/// Owners can gate entry to their parcel on holdings of other collections
+/// and link it to parcels in other worlds through portals. Hashes of full
+/// serialized scenes can be committed to anchor what was built when.
+/// Rectangular districts route a share of rental income on their parcels to
+/// a district treasury, such as a land DAO. Parcel owners elect a council
+/// per district that maintains its metadata and entry rules, and can lock
+/// parcels to vote on critical governance proposals. World names are reserved against
+/// a deposit that is returned once the world publishes content.
+#[ink::contract]
+mod vr_land {
+    use ink::prelude::{string::String, vec, vec::Vec};
+    use ink::storage::{traits::StorageKey, Mapping};
+    use ink::codegen::TraitCallBuilder;
+    #[cfg(feature = "rental")]
+    use polka_space_nft_core::royalty_amount;
+    use polka_space_nft_core::{
+        checkpoints::{self, Checkpoint, Checkpoints},
+        delegation::Delegation,
+        index::OwnerIndex,
+        is_valid_recipient, AccountLinks, BalanceHistory, Enumerable, ItemRef, PSP34Core, SacrificeReceiver, SceneContainer,
+        TransferHook, Votes,
+    };
+    use scale::{Decode, Encode};
+
+    /// Maximum number of parcels that can be minted in a single region call
+    pub const MAX_REGION_PARCELS: u32 = 100;
+
+    /// Maximum number of items anchored in a single parcel's scene
+    pub const MAX_SCENE_ITEMS: u32 = 256;
+
+    /// Maximum number of builders per parcel
+    pub const MAX_BUILDERS: u32 = 16;
+
+    /// Maximum number of gate rules per parcel
+    pub const MAX_GATE_RULES: u32 = 8;
+
+    /// Most linked accounts a gate rule counts tokens of
+    pub const MAX_LINKED_ACCOUNTS: usize = 8;
+
+    /// Maximum number of outgoing portals per parcel
+    pub const MAX_PORTALS: u32 = 8;
+
+    /// Maximum number of entries returned by paginated queries
+    pub const MAX_PAGE_SIZE: u32 = 100;
+
+    /// Maximum number of districts per world
+    pub const MAX_DISTRICTS_PER_WORLD: u32 = 16;
+
+    /// Upper bound for a district's share of rental income (basis points)
+    pub const MAX_DISTRICT_SHARE_BPS: u16 = 5_000;
+
+    /// Maximum number of seats on a district council
+    pub const MAX_COUNCIL_SEATS: u8 = 9;
+
+    /// Maximum number of candidates in a council election
+    pub const MAX_COUNCIL_CANDIDATES: u32 = 32;
+
+    /// Shortest lock that counts as a governance sacrifice, about a week of
+    /// 6-second blocks. Parcels can only be locked, never burned, since
+    /// their coordinates can't be freed.
+    pub const MIN_SACRIFICE_LOCK: BlockNumber = 100_800;
+
+    /// Maximum length of a world name in bytes
+    pub const MAX_WORLD_NAME_LEN: usize = 64;
+
+    /// Event emitted for parcel transfers (and mints, with `from: None`)
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        #[ink(topic)]
+        token_id: u32,
+    }
+
+    /// Event emitted when an owner changes who votes with its parcels
+    #[ink(event)]
+    pub struct DelegateChanged {
+        #[ink(topic)]
+        delegator: AccountId,
+        from_delegate: AccountId,
+        #[ink(topic)]
+        to_delegate: AccountId,
+    }
+
+    /// Event emitted when a parcel is minted at a coordinate
+    #[ink(event)]
+    pub struct ParcelMinted {
+        #[ink(topic)]
+        token_id: u32,
+        #[ink(topic)]
+        world_id: u32,
+        x: i32,
+        y: i32,
+    }
+
+    /// Event emitted when parcels are merged into an estate
+    #[ink(event)]
+    pub struct EstateMerged {
+        #[ink(topic)]
+        estate_id: u32,
+        parcels: Vec<u32>,
+    }
+
+    /// Event emitted when an estate is split back into its parcels
+    #[ink(event)]
+    pub struct EstateSplit {
+        #[ink(topic)]
+        estate_id: u32,
+        parcels: Vec<u32>,
+    }
+
+    /// Event emitted when an item is anchored into a parcel's scene
+    #[ink(event)]
+    pub struct ItemPlaced {
+        #[ink(topic)]
+        parcel_id: u32,
+        #[ink(topic)]
+        item: ItemRef,
+        transform: Transform,
+    }
+
+    /// Event emitted when an item is removed from a parcel's scene
+    #[ink(event)]
+    pub struct ItemRemoved {
+        #[ink(topic)]
+        parcel_id: u32,
+        #[ink(topic)]
+        item: ItemRef,
+    }
+
+    /// Event emitted when a collaborator is granted scene-edit rights
+    #[ink(event)]
+    pub struct BuilderAdded {
+        #[ink(topic)]
+        parcel_id: u32,
+        #[ink(topic)]
+        builder: AccountId,
+    }
+
+    /// Event emitted when a collaborator loses scene-edit rights
+    #[ink(event)]
+    pub struct BuilderRemoved {
+        #[ink(topic)]
+        parcel_id: u32,
+        #[ink(topic)]
+        builder: AccountId,
+    }
+
+    /// Event emitted when a parcel's gate rule is added, changed or removed
+    #[ink(event)]
+    pub struct GateSet {
+        #[ink(topic)]
+        parcel_id: u32,
+        #[ink(topic)]
+        collection: AccountId,
+        /// Required balance; zero means the rule was removed
+        min_balance: u32,
+        count_linked: bool,
+    }
+
+    /// Event emitted when a portal is opened from a parcel
+    #[ink(event)]
+    pub struct PortalCreated {
+        #[ink(topic)]
+        from_parcel: u32,
+        #[ink(topic)]
+        to_world: u32,
+        to_parcel: u32,
+    }
+
+    /// Event emitted when a portal is closed
+    #[ink(event)]
+    pub struct PortalRemoved {
+        #[ink(topic)]
+        from_parcel: u32,
+        #[ink(topic)]
+        to_world: u32,
+        to_parcel: u32,
+    }
+
+    /// Event emitted when a scene snapshot hash is committed
+    #[ink(event)]
+    pub struct SceneSnapshotCommitted {
+        #[ink(topic)]
+        parcel_id: u32,
+        #[ink(topic)]
+        snapshot_hash: Hash,
+        /// Position of the snapshot in the parcel's history
+        index: u32,
+        committer: AccountId,
+    }
+
+    /// Event emitted when a district is created
+    #[ink(event)]
+    pub struct DistrictCreated {
+        #[ink(topic)]
+        district_id: u32,
+        #[ink(topic)]
+        world_id: u32,
+        treasury: AccountId,
+        share_bps: u16,
+    }
+
+    /// Event emitted when a district's treasury or share changes
+    #[ink(event)]
+    pub struct DistrictConfigured {
+        #[ink(topic)]
+        district_id: u32,
+        treasury: AccountId,
+        share_bps: u16,
+    }
+
+    /// Event emitted when an owner locks a parcel to vote on a critical proposal
+    #[ink(event)]
+    pub struct ParcelLocked {
+        #[ink(topic)]
+        parcel_id: u32,
+        #[ink(topic)]
+        governance: AccountId,
+        proposal_id: u32,
+        /// Last block of the lock
+        until: BlockNumber,
+    }
+
+    /// Event emitted when a district council election opens for nominations
+    #[ink(event)]
+    pub struct CouncilElectionStarted {
+        #[ink(topic)]
+        district_id: u32,
+        round: u32,
+        nomination_end: BlockNumber,
+        voting_end: BlockNumber,
+    }
+
+    /// Event emitted when a parcel owner stands for a district council
+    #[ink(event)]
+    pub struct CouncilCandidateNominated {
+        #[ink(topic)]
+        district_id: u32,
+        #[ink(topic)]
+        candidate: AccountId,
+    }
+
+    /// Event emitted when a parcel or estate votes in a council election
+    #[ink(event)]
+    pub struct CouncilVoteCast {
+        #[ink(topic)]
+        district_id: u32,
+        #[ink(topic)]
+        voter: AccountId,
+        token_id: u32,
+        candidates: Vec<AccountId>,
+        /// Number of the token's parcels inside the district
+        weight: u32,
+    }
+
+    /// Event emitted when a council election is closed and the council seated
+    #[ink(event)]
+    pub struct CouncilElected {
+        #[ink(topic)]
+        district_id: u32,
+        members: Vec<AccountId>,
+        /// Last block of the council's term
+        until: BlockNumber,
+    }
+
+    /// Event emitted when a district council changes the district's metadata
+    #[ink(event)]
+    pub struct DistrictMetadataSet {
+        #[ink(topic)]
+        district_id: u32,
+        uri: String,
+    }
+
+    /// Event emitted when a district council changes the district's entry rules
+    #[ink(event)]
+    pub struct DistrictGatesSet {
+        #[ink(topic)]
+        district_id: u32,
+        gates: Vec<GateRule>,
+    }
+
+    /// Event emitted when a parcel owner offers it for rent
+    #[cfg(feature = "rental")]
+    #[ink(event)]
+    pub struct RentalOffered {
+        #[ink(topic)]
+        parcel_id: u32,
+        price_per_block: Balance,
+        max_blocks: BlockNumber,
+    }
+
+    /// Event emitted when a parcel owner withdraws a rental offer
+    #[cfg(feature = "rental")]
+    #[ink(event)]
+    pub struct RentalOfferCancelled {
+        #[ink(topic)]
+        parcel_id: u32,
+    }
+
+    /// Event emitted when a parcel is rented
+    #[cfg(feature = "rental")]
+    #[ink(event)]
+    pub struct ParcelRented {
+        #[ink(topic)]
+        parcel_id: u32,
+        #[ink(topic)]
+        renter: AccountId,
+        /// Last block of the rental
+        until: BlockNumber,
+        price: Balance,
+        /// Part of the price credited to the parcel's district
+        district_share: Balance,
+    }
+
+    /// Event emitted when a district treasury withdraws its rental income
+    #[cfg(feature = "rental")]
+    #[ink(event)]
+    pub struct DistrictRevenueWithdrawn {
+        #[ink(topic)]
+        district_id: u32,
+        #[ink(topic)]
+        treasury: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when a world name is reserved
+    #[ink(event)]
+    pub struct WorldNameReserved {
+        #[ink(topic)]
+        world_id: u32,
+        #[ink(topic)]
+        owner: AccountId,
+        name: String,
+        deposit: Balance,
+    }
+
+    /// Event emitted when a world is found to publish content and its name
+    /// deposit is returned
+    #[ink(event)]
+    pub struct WorldPublished {
+        #[ink(topic)]
+        world_id: u32,
+        #[ink(topic)]
+        owner: AccountId,
+        deposit: Balance,
+    }
+
+    /// Event emitted when the admin takes back a squatted world name
+    #[ink(event)]
+    pub struct WorldNameReclaimed {
+        #[ink(topic)]
+        world_id: u32,
+        #[ink(topic)]
+        owner: AccountId,
+        name: String,
+        /// Forfeited deposit, paid to the admin
+        deposit: Balance,
+    }
+
+    /// Location of a parcel on a world grid
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Coordinates {
+        pub world_id: u32,
+        pub x: i32,
+        pub y: i32,
+    }
+
+    impl Coordinates {
+        /// Whether two coordinates share an edge in the same world
+        pub fn is_adjacent(&self, other: &Coordinates) -> bool {
+            self.world_id == other.world_id
+                && self.x.abs_diff(other.x).checked_add(self.y.abs_diff(other.y)) == Some(1)
+        }
+    }
+
+    /// Placement of an item relative to the parcel origin.
+    /// Fixed-point values since contracts can't use floats.
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Transform {
+        /// Position in millimetres (x, y, z)
+        pub position: [i32; 3],
+        /// Euler rotation in millidegrees (x, y, z)
+        pub rotation: [i32; 3],
+        /// Uniform scale in thousandths (1000 = 1.0)
+        pub scale: u32,
+    }
+
+    /// An item anchored in a parcel's scene
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct SceneItem {
+        pub item: ItemRef,
+        pub transform: Transform,
+    }
+
+    /// Entry requirement: hold at least `min_balance` tokens of `collection`
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct GateRule {
+        /// Contract implementing `PSP34Core`
+        pub collection: AccountId,
+        pub min_balance: u32,
+        /// Also count tokens held by accounts linked to the entrant, as the
+        /// collection's `AccountLinks` registry reports them
+        pub count_linked: bool,
+    }
+
+    /// Destination of a portal
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Portal {
+        pub to_world: u32,
+        pub to_parcel: u32,
+    }
+
+    /// Commitment to a parcel's full serialized scene at a point in time
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct SceneSnapshot {
+        pub snapshot_hash: Hash,
+        pub block: BlockNumber,
+        pub committer: AccountId,
+    }
+
+    /// A rectangle of a world whose parcels share part of their rental
+    /// income with the district treasury
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct District {
+        pub world_id: u32,
+        pub x0: i32,
+        pub y0: i32,
+        pub x1: i32,
+        pub y1: i32,
+        /// Account rental income shares are paid to, and that configures the district
+        pub treasury: AccountId,
+        /// Share of rental income routed to the treasury (basis points)
+        pub share_bps: u16,
+    }
+
+    impl District {
+        /// Whether a coordinate lies inside the district
+        pub fn contains(&self, c: &Coordinates) -> bool {
+            c.world_id == self.world_id && (self.x0..=self.x1).contains(&c.x) && (self.y0..=self.y1).contains(&c.y)
+        }
+
+        /// Whether two districts share any coordinate
+        pub fn overlaps(&self, other: &District) -> bool {
+            self.world_id == other.world_id
+                && self.x0 <= other.x1
+                && other.x0 <= self.x1
+                && self.y0 <= other.y1
+                && other.y0 <= self.y1
+        }
+    }
+
+    /// Seats and timing of a district's council elections
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct CouncilConfig {
+        pub seats: u8,
+        /// Blocks candidates can be nominated for after an election starts
+        pub nomination_blocks: BlockNumber,
+        /// Blocks owners can vote for after nominations close
+        pub voting_blocks: BlockNumber,
+        /// Length of an elected council's term
+        pub term_blocks: BlockNumber,
+    }
+
+    /// A candidate and the parcels voting for them so far
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct CouncilCandidate {
+        pub account: AccountId,
+        pub votes: u32,
+    }
+
+    /// A district's council election in progress
+    #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct CouncilElection {
+        /// Number of the election in the district, starting at 1
+        pub round: u32,
+        /// Seats up for election, from the config when it started
+        pub seats: u8,
+        /// Term of the elected council, from the config when it started
+        pub term_blocks: BlockNumber,
+        /// Last block of nominations
+        pub nomination_end: BlockNumber,
+        /// Last block of voting
+        pub voting_end: BlockNumber,
+        /// Candidates in nomination order
+        pub candidates: Vec<CouncilCandidate>,
+    }
+
+    /// A district's elected council, which may change the district's
+    /// metadata and entry rules until `until`
+    #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Council {
+        pub members: Vec<AccountId>,
+        /// Last block of the council's term
+        pub until: BlockNumber,
+    }
+
+    /// Terms a parcel owner offers it for rent on
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct RentalOffer {
+        pub price_per_block: Balance,
+        /// Longest rental accepted, in blocks
+        pub max_blocks: BlockNumber,
+    }
+
+    /// A parcel's current renter, who may edit its scene until `until`
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Rental {
+        pub renter: AccountId,
+        /// Last block of the rental
+        pub until: BlockNumber,
+    }
+
+    /// A world's reserved name
+    #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct WorldName {
+        pub name: String,
+        /// Account that reserved the name
+        pub owner: AccountId,
+        /// Deposit held until the world publishes content
+        pub deposit: Balance,
+        /// Whether the world has published content and the deposit was returned
+        pub published: bool,
+    }
+
+    /// Errors returned by the land contract
+    #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Caller is not the contract admin
+        NotAdmin,
+        /// Caller does not own the parcel
+        NotOwner,
+        /// Parcel does not exist
+        TokenNotFound,
+        /// A parcel already exists at the coordinate
+        CoordinatesTaken,
+        /// Region corners are not ordered (x0 <= x1, y0 <= y1)
+        InvalidRegion,
+        /// Region covers more than `MAX_REGION_PARCELS` parcels
+        RegionTooLarge,
+        /// Merge needs at least two distinct parcels
+        InvalidMerge,
+        /// Parcels to merge do not form one connected area
+        NotAdjacent,
+        /// Token is not an estate
+        NotEstate,
+        /// Token is an estate, not a plain parcel
+        IsEstate,
+        /// Item is already anchored in a scene
+        ItemAlreadyPlaced,
+        /// Item is not anchored in this parcel's scene
+        ItemNotInScene,
+        /// Scene already holds `MAX_SCENE_ITEMS` items
+        SceneFull,
+        /// Parcel still has items in its scene
+        SceneNotEmpty,
+        /// Account is already a builder on the parcel
+        BuilderAlreadyAdded,
+        /// Account is not a builder on the parcel
+        BuilderNotFound,
+        /// Parcel already has `MAX_BUILDERS` builders
+        TooManyBuilders,
+        /// Parcel already has `MAX_GATE_RULES` gate rules
+        TooManyGateRules,
+        /// Portal to this destination already exists
+        PortalExists,
+        /// No portal to this destination
+        PortalNotFound,
+        /// Parcel already has `MAX_PORTALS` portals
+        TooManyPortals,
+        /// Parcels can't be sent to the zero address or to this contract
+        InvalidRecipient,
+        /// Sender and recipient are the same account
+        SelfTransfer,
+        /// A counter or balance would overflow
+        Overflow,
+        /// District share above `MAX_DISTRICT_SHARE_BPS`
+        InvalidShare,
+        /// District overlaps an existing one
+        DistrictOverlap,
+        /// World already has `MAX_DISTRICTS_PER_WORLD` districts
+        TooManyDistricts,
+        /// District does not exist
+        DistrictNotFound,
+        /// Caller is not the district treasury
+        NotTreasury,
+        /// Parcel is not offered for rent
+        NotForRent,
+        /// Parcel is currently rented
+        Rented,
+        /// Rental length is zero or longer than the offer allows
+        InvalidRentalTerm,
+        /// The value sent doesn't match the rental price
+        WrongPayment,
+        /// Paying out native tokens failed
+        TransferFailed,
+        /// World name is empty or longer than `MAX_WORLD_NAME_LEN`
+        InvalidName,
+        /// World name or world already reserved
+        NameTaken,
+        /// World has no reserved name
+        NameNotReserved,
+        /// World already published content
+        AlreadyPublished,
+        /// Caller is not a curator
+        NotCurator,
+        /// Council seats are zero or above `MAX_COUNCIL_SEATS`, or a period is zero
+        InvalidCouncilConfig,
+        /// District has no council elections configured
+        CouncilNotConfigured,
+        /// District already has an election running or a council in office
+        CouncilInOffice,
+        /// No election running in the district
+        NoElection,
+        /// The election is not in the phase this needs
+        WrongElectionPhase,
+        /// Token has no parcels inside the district
+        NotInDistrict,
+        /// Account is already standing in the election
+        AlreadyNominated,
+        /// Election already has `MAX_COUNCIL_CANDIDATES` candidates
+        TooManyCandidates,
+        /// Ballot names a non-candidate, repeats one or exceeds the seats
+        InvalidBallot,
+        /// One of the token's parcels already voted in this election
+        AlreadyVoted,
+        /// Caller is not on the district's council
+        NotCouncilMember,
+        /// Parcel is locked as a governance sacrifice
+        Locked,
+        /// Lock shorter than `MIN_SACRIFICE_LOCK`
+        LockTooShort,
+        /// The governance contract didn't accept the sacrifice
+        SacrificeRejected,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Main storage for the land contract
+    #[ink(storage)]
+    pub struct VrLand {
+        /// Token ID counter
+        next_token_id: u32,
+        /// Parcel owner mapping
+        token_owner: Mapping<u32, AccountId>,
+        /// Owner parcel count
+        balances: Mapping<AccountId, u32>,
+        /// Owner's parcels by position (`0..balance`)
+        owned_token_by_index: Mapping<(AccountId, u32), u32>,
+        /// Position of each parcel in its owner's index
+        owned_token_index: Mapping<u32, u32>,
+        /// Parcel count history of each owner, keyed by (owner, position)
+        balance_checkpoints: Mapping<(AccountId, u32), Checkpoint>,
+        /// Number of parcel count checkpoints per owner
+        balance_checkpoint_count: Mapping<AccountId, u32>,
+        /// Account each delegator's parcels vote with
+        delegates: Mapping<AccountId, AccountId>,
+        /// Voting power history of each account, keyed by (account, position)
+        vote_checkpoints: Mapping<(AccountId, u32), Checkpoint>,
+        /// Number of voting power checkpoints per account
+        vote_checkpoint_count: Mapping<AccountId, u32>,
+        /// Coordinates of each parcel
+        parcel_coordinates: Mapping<u32, Coordinates>,
+        /// Reverse index from coordinates to parcel (or the estate containing it)
+        parcel_by_coordinates: Mapping<Coordinates, u32>,
+        /// Constituent parcels of each estate
+        estate_parcels: Mapping<u32, Vec<u32>>,
+        /// Items anchored in each parcel's scene
+        scene_items: Mapping<u32, Vec<SceneItem>>,
+        /// Parcel each item is currently anchored in
+        item_parcel: Mapping<ItemRef, u32>,
+        /// Collaborators allowed to edit each parcel's scene
+        parcel_builders: Mapping<u32, Vec<AccountId>>,
+        /// Entry rules for each parcel
+        parcel_gates: Mapping<u32, Vec<GateRule>>,
+        /// Outgoing portals of each parcel
+        parcel_portals: Mapping<u32, Vec<Portal>>,
+        /// Number of snapshots committed for each parcel
+        snapshot_count: Mapping<u32, u32>,
+        /// Snapshot history, keyed by (parcel, index)
+        scene_snapshots: Mapping<(u32, u32), SceneSnapshot>,
+        /// Admin account allowed to mint parcels
+        admin: AccountId,
+        /// Districts by ID
+        districts: Mapping<u32, District>,
+        district_count: u32,
+        /// Districts of each world
+        world_districts: Mapping<u32, Vec<u32>>,
+        /// Last block each parcel locked as a governance sacrifice stays locked
+        sacrifice_locks: Mapping<u32, BlockNumber>,
+        /// Council election settings of each district
+        council_configs: Mapping<u32, CouncilConfig>,
+        /// Election running in each district
+        council_elections: Mapping<u32, CouncilElection>,
+        /// Number of elections started in each district
+        council_rounds: Mapping<u32, u32>,
+        /// Latest election round each parcel voted in. Districts don't
+        /// overlap, so a parcel only ever votes in one district's rounds.
+        council_votes: Mapping<u32, u32>,
+        /// Latest council of each district, in office or not
+        councils: Mapping<u32, Council>,
+        /// Metadata URI each district council publishes
+        district_metadata: Mapping<u32, String>,
+        /// Entry rules applying to every parcel of a district
+        district_gates: Mapping<u32, Vec<GateRule>>,
+        /// Open rental offers
+        #[cfg(feature = "rental")]
+        rental_offers: Mapping<u32, RentalOffer>,
+        /// Latest rental of each parcel, active or expired
+        #[cfg(feature = "rental")]
+        rentals: Mapping<u32, Rental>,
+        /// Rental income credited to each district and not yet withdrawn
+        #[cfg(feature = "rental")]
+        district_revenue: Mapping<u32, Balance>,
+        /// Accounts that may verify a world has published content
+        curators: Mapping<AccountId, ()>,
+        /// Deposit required to reserve a world name
+        name_deposit: Balance,
+        /// Reserved name of each world
+        world_names: Mapping<u32, WorldName>,
+        /// World each reserved name belongs to
+        world_by_name: Mapping<String, u32>,
+    }
+
+    impl VrLand {
+        /// Constructor to initialize an empty land registry
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                next_token_id: 1,
+                token_owner: Mapping::default(),
+                balances: Mapping::default(),
+                owned_token_by_index: Mapping::default(),
+                owned_token_index: Mapping::default(),
+                balance_checkpoints: Mapping::default(),
+                balance_checkpoint_count: Mapping::default(),
+                delegates: Mapping::default(),
+                vote_checkpoints: Mapping::default(),
+                vote_checkpoint_count: Mapping::default(),
+                parcel_coordinates: Mapping::default(),
+                parcel_by_coordinates: Mapping::default(),
+                estate_parcels: Mapping::default(),
+                scene_items: Mapping::default(),
+                item_parcel: Mapping::default(),
+                parcel_builders: Mapping::default(),
+                parcel_gates: Mapping::default(),
+                parcel_portals: Mapping::default(),
+                snapshot_count: Mapping::default(),
+                scene_snapshots: Mapping::default(),
+                admin: Self::env().caller(),
+                districts: Mapping::default(),
+                district_count: 0,
+                world_districts: Mapping::default(),
+                sacrifice_locks: Mapping::default(),
+                council_configs: Mapping::default(),
+                council_elections: Mapping::default(),
+                council_rounds: Mapping::default(),
+                council_votes: Mapping::default(),
+                councils: Mapping::default(),
+                district_metadata: Mapping::default(),
+                district_gates: Mapping::default(),
+                #[cfg(feature = "rental")]
+                rental_offers: Mapping::default(),
+                #[cfg(feature = "rental")]
+                rentals: Mapping::default(),
+                #[cfg(feature = "rental")]
+                district_revenue: Mapping::default(),
+                curators: Mapping::default(),
+                name_deposit: 0,
+                world_names: Mapping::default(),
+                world_by_name: Mapping::default(),
+            }
+        }
+
+        /// Get the admin account
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// Get the parcel at a coordinate, if one has been minted.
+        /// Returns the estate ID while the parcel is merged into an estate.
+        #[ink(message)]
+        pub fn parcel_at(&self, world_id: u32, x: i32, y: i32) -> Option<u32> {
+            self.parcel_by_coordinates.get(Coordinates { world_id, x, y })
+        }
+
+        /// Get the coordinates of a parcel
+        #[ink(message)]
+        pub fn coordinates_of(&self, token_id: u32) -> Option<Coordinates> {
+            self.parcel_coordinates.get(token_id)
+        }
+
+        /// Get the parcels bordering a parcel, in north, east, south, west order.
+        /// Merged neighbours resolve to their estate; estates themselves have no neighbours.
+        #[ink(message)]
+        pub fn neighbors_of(&self, parcel_id: u32) -> Vec<Option<u32>> {
+            let c = match self.parcel_coordinates.get(parcel_id) {
+                Some(c) if !self.is_estate(parcel_id) => c,
+                _ => return Vec::new(),
+            };
+
+            [(0, 1), (1, 0), (0, -1), (-1, 0)]
+                .iter()
+                .map(|&(dx, dy)| {
+                    let x = c.x.checked_add(dx)?;
+                    let y = c.y.checked_add(dy)?;
+                    self.parcel_at(c.world_id, x, y)
+                })
+                .collect()
+        }
+
+        /// Scan up to `limit` cells of the inclusive rectangle (x0, y0)..=(x1, y1),
+        /// starting at cell index `start` in row-major order, and return the minted
+        /// parcels found. Callers page through by advancing `start` by `limit`.
+        #[ink(message)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn parcels_in_region(
+            &self,
+            world_id: u32,
+            x0: i32,
+            y0: i32,
+            x1: i32,
+            y1: i32,
+            start: u32,
+            limit: u32,
+        ) -> Vec<(Coordinates, u32)> {
+            if x0 > x1 || y0 > y1 {
+                return Vec::new();
+            }
+
+            // At most 2^32 each, so these can't saturate
+            let width = u64::from(x1.abs_diff(x0)).saturating_add(1);
+            let height = u64::from(y1.abs_diff(y0)).saturating_add(1);
+            let cells = width.saturating_mul(height);
+            let end = (start as u64)
+                .saturating_add(limit.min(MAX_PAGE_SIZE) as u64)
+                .min(cells);
+
+            (start as u64..end)
+                .filter_map(|cell| {
+                    let coordinates = Coordinates {
+                        world_id,
+                        x: i64::from(x0).saturating_add(cell.checked_rem(width)? as i64) as i32,
+                        y: i64::from(y0).saturating_add(cell.checked_div(width)? as i64) as i32,
+                    };
+                    self.parcel_by_coordinates
+                        .get(coordinates)
+                        .map(|token_id| (coordinates, token_id))
+                })
+                .collect()
+        }
+
+        /// Get the parcels merged into an estate
+        #[ink(message)]
+        pub fn estate_parcels(&self, estate_id: u32) -> Vec<u32> {
+            self.estate_parcels.get(estate_id).unwrap_or_default()
+        }
+
+        /// Check whether a token is an estate
+        #[ink(message)]
+        pub fn is_estate(&self, token_id: u32) -> bool {
+            self.estate_parcels.contains(token_id)
+        }
+
+        /// Get everything a client must render for a parcel
+        #[ink(message)]
+        pub fn scene_contents(&self, parcel_id: u32) -> Vec<SceneItem> {
+            self.scene_items.get(parcel_id).unwrap_or_default()
+        }
+
+        /// Get the builders of a parcel
+        #[ink(message)]
+        pub fn builders_of(&self, parcel_id: u32) -> Vec<AccountId> {
+            self.parcel_builders.get(parcel_id).unwrap_or_default()
+        }
+
+        /// Check whether an account may edit a parcel's scene without owning it
+        #[ink(message)]
+        pub fn is_builder(&self, parcel_id: u32, account: AccountId) -> bool {
+            self.builders_of(parcel_id).contains(&account)
+        }
+
+        /// Get the gate rules of a parcel
+        #[ink(message)]
+        pub fn gates_of(&self, parcel_id: u32) -> Vec<GateRule> {
+            self.parcel_gates.get(parcel_id).unwrap_or_default()
+        }
+
+        /// Get the outgoing portals of a parcel
+        #[ink(message)]
+        pub fn portals_of(&self, parcel_id: u32) -> Vec<Portal> {
+            self.parcel_portals.get(parcel_id).unwrap_or_default()
+        }
+
+        /// Get the number of snapshots committed for a parcel
+        #[ink(message)]
+        pub fn snapshot_count(&self, parcel_id: u32) -> u32 {
+            self.snapshot_count.get(parcel_id).unwrap_or(0)
+        }
+
+        /// Get a parcel's snapshot history, oldest first, starting at `start`
+        #[ink(message)]
+        pub fn scene_snapshots(&self, parcel_id: u32, start: u32, limit: u32) -> Vec<SceneSnapshot> {
+            let end = start
+                .saturating_add(limit.min(MAX_PAGE_SIZE))
+                .min(self.snapshot_count(parcel_id));
+            (start..end)
+                .filter_map(|index| self.scene_snapshots.get((parcel_id, index)))
+                .collect()
+        }
+
+        /// Get the most recent snapshot of a parcel
+        #[ink(message)]
+        pub fn latest_snapshot(&self, parcel_id: u32) -> Option<SceneSnapshot> {
+            let last = self.snapshot_count(parcel_id).checked_sub(1)?;
+            self.scene_snapshots.get((parcel_id, last))
+        }
+
+        /// Mint a single parcel (admin only)
+        #[ink(message)]
+        pub fn mint_parcel(&mut self, to: AccountId, world_id: u32, x: i32, y: i32) -> Result<u32> {
+            self.ensure_admin()?;
+            self.ensure_valid_recipient(to)?;
+            if self.parcel_by_coordinates.contains(Coordinates { world_id, x, y }) {
+                return Err(Error::CoordinatesTaken);
+            }
+            self.mint_at(to, Coordinates { world_id, x, y })
+        }
+
+        /// Mint every parcel in the inclusive rectangle (x0, y0)..=(x1, y1) (admin only).
+        /// Fails without minting anything if any coordinate is already taken.
+        #[ink(message)]
+        pub fn mint_region(
+            &mut self,
+            to: AccountId,
+            world_id: u32,
+            x0: i32,
+            y0: i32,
+            x1: i32,
+            y1: i32,
+        ) -> Result<Vec<u32>> {
+            self.ensure_admin()?;
+            self.ensure_valid_recipient(to)?;
+            if x0 > x1 || y0 > y1 {
+                return Err(Error::InvalidRegion);
+            }
+
+            // At most 2^32 each, so these can't saturate
+            let width = u64::from(x1.abs_diff(x0)).saturating_add(1);
+            let height = u64::from(y1.abs_diff(y0)).saturating_add(1);
+            if width.saturating_mul(height) > MAX_REGION_PARCELS as u64 {
+                return Err(Error::RegionTooLarge);
+            }
+
+            // Validate the whole region before touching storage
+            for x in x0..=x1 {
+                for y in y0..=y1 {
+                    if self.parcel_by_coordinates.contains(Coordinates { world_id, x, y }) {
+                        return Err(Error::CoordinatesTaken);
+                    }
+                }
+            }
+
+            let mut minted = Vec::new();
+            for x in x0..=x1 {
+                for y in y0..=y1 {
+                    minted.push(self.mint_at(to, Coordinates { world_id, x, y })?);
+                }
+            }
+            Ok(minted)
+        }
+
+        /// Transfer a parcel to another address
+        #[ink(message)]
+        pub fn transfer(&mut self, to: AccountId, token_id: u32) -> Result<()> {
+            let owner = self.env().caller();
+            self.ensure_valid_recipient(to)?;
+            self.ensure_owner(token_id)?;
+            if to == owner {
+                return Err(Error::SelfTransfer);
+            }
+            self.ensure_unlocked(token_id)?;
+
+            self.remove_token_from(owner, token_id);
+            self.add_token_to(to, token_id)?;
+
+            self.env().emit_event(Transfer {
+                from: Some(owner),
+                to: Some(to),
+                token_id,
+            });
+
+            Ok(())
+        }
+
+        /// Get the last block a parcel locked as a governance sacrifice stays
+        /// locked, while the lock lasts
+        #[ink(message)]
+        pub fn locked_until(&self, parcel_id: u32) -> Option<BlockNumber> {
+            self.sacrifice_locks
+                .get(parcel_id)
+                .filter(|&until| self.env().block_number() <= until)
+        }
+
+        /// Lock an owned parcel or estate for at least `MIN_SACRIFICE_LOCK`
+        /// blocks for an amplified vote on a critical proposal of `governance`.
+        /// Reverts if the governance contract doesn't accept it.
+        #[ink(message)]
+        pub fn sacrifice(
+            &mut self,
+            governance: AccountId,
+            proposal_id: u32,
+            parcel_id: u32,
+            support: bool,
+            lock_blocks: BlockNumber,
+        ) -> Result<()> {
+            let holder = self.env().caller();
+            self.ensure_owner(parcel_id)?;
+            self.ensure_unlocked(parcel_id)?;
+            if lock_blocks < MIN_SACRIFICE_LOCK {
+                return Err(Error::LockTooShort);
+            }
+
+            // The lock starts in the current block
+            let until = self.env().block_number().saturating_add(lock_blocks).saturating_sub(1);
+            self.sacrifice_locks.insert(parcel_id, &until);
+            self.env().emit_event(ParcelLocked {
+                parcel_id,
+                governance,
+                proposal_id,
+                until,
+            });
+
+            let mut receiver: ink::contract_ref!(SacrificeReceiver) = governance.into();
+            let accepted = receiver
+                .call_mut()
+                .on_sacrifice(holder, parcel_id, proposal_id, support, false)
+                .try_invoke();
+            if !matches!(accepted, Ok(Ok(true))) {
+                return Err(Error::SacrificeRejected);
+            }
+            Ok(())
+        }
+
+        /// Let `to` vote with the caller's parcels, now and as they come and go
+        #[ink(message)]
+        pub fn delegate_votes(&mut self, to: AccountId) -> Result<()> {
+            self.ensure_valid_recipient(to)?;
+            self.set_delegate(Some(to))
+        }
+
+        /// Vote with the caller's own parcels again
+        #[ink(message)]
+        pub fn undelegate(&mut self) -> Result<()> {
+            self.set_delegate(None)
+        }
+
+        /// Merge connected parcels owned by the caller into a new estate token.
+        /// The parcels are burned; their coordinates now resolve to the estate.
+        #[ink(message)]
+        pub fn merge(&mut self, parcels: Vec<u32>) -> Result<u32> {
+            let caller = self.env().caller();
+
+            if parcels.len() < 2 {
+                return Err(Error::InvalidMerge);
+            }
+            if parcels.len() > MAX_REGION_PARCELS as usize {
+                return Err(Error::RegionTooLarge);
+            }
+
+            let mut coordinates = Vec::with_capacity(parcels.len());
+            for (i, &token_id) in parcels.iter().enumerate() {
+                if parcels.iter().take(i).any(|&seen| seen == token_id) {
+                    return Err(Error::InvalidMerge);
+                }
+                self.ensure_owner(token_id)?;
+                if self.is_estate(token_id) {
+                    return Err(Error::IsEstate);
+                }
+                if self.scene_items.contains(token_id) {
+                    return Err(Error::SceneNotEmpty);
+                }
+                self.ensure_not_rented(token_id)?;
+                self.ensure_unlocked(token_id)?;
+                coordinates.push(self.parcel_coordinates.get(token_id).ok_or(Error::TokenNotFound)?);
+            }
+
+            if !Self::is_connected(&coordinates) {
+                return Err(Error::NotAdjacent);
+            }
+
+            // Burn the constituent parcels
+            for &token_id in &parcels {
+                self.burn(caller, token_id);
+            }
+
+            // Mint the estate and point the covered coordinates at it
+            let estate_id = self.mint_token(caller)?;
+            for c in &coordinates {
+                self.parcel_by_coordinates.insert(c, &estate_id);
+            }
+            self.estate_parcels.insert(estate_id, &parcels);
+
+            self.env().emit_event(EstateMerged {
+                estate_id,
+                parcels,
+            });
+
+            Ok(estate_id)
+        }
+
+        /// Split an estate owned by the caller back into its original parcels
+        #[ink(message)]
+        pub fn split(&mut self, estate_id: u32) -> Result<Vec<u32>> {
+            let caller = self.env().caller();
+            self.ensure_owner(estate_id)?;
+            let parcels = self.estate_parcels.get(estate_id).ok_or(Error::NotEstate)?;
+            if self.scene_items.contains(estate_id) {
+                return Err(Error::SceneNotEmpty);
+            }
+            self.ensure_not_rented(estate_id)?;
+            self.ensure_unlocked(estate_id)?;
+
+            self.burn(caller, estate_id);
+            self.estate_parcels.remove(estate_id);
+
+            // Re-mint the parcels under their original IDs
+            for &token_id in &parcels {
+                if let Some(c) = self.parcel_coordinates.get(token_id) {
+                    self.parcel_by_coordinates.insert(c, &token_id);
+                }
+                self.add_token_to(caller, token_id)?;
+                self.env().emit_event(Transfer {
+                    from: None,
+                    to: Some(caller),
+                    token_id,
+                });
+            }
+
+            self.env().emit_event(EstateSplit {
+                estate_id,
+                parcels: parcels.clone(),
+            });
+
+            Ok(parcels)
+        }
+
+        /// Get a district
+        #[ink(message)]
+        pub fn district(&self, district_id: u32) -> Option<District> {
+            self.districts.get(district_id)
+        }
+
+        /// Get the district a parcel or estate lies in. Estates belong to the
+        /// district of their first parcel.
+        #[ink(message)]
+        pub fn district_of(&self, parcel_id: u32) -> Option<u32> {
+            let coordinates = self.location_of(parcel_id)?;
+            self.world_districts
+                .get(coordinates.world_id)
+                .unwrap_or_default()
+                .into_iter()
+                .find(|&district_id| {
+                    self.districts
+                        .get(district_id)
+                        .is_some_and(|district| district.contains(&coordinates))
+                })
+        }
+
+        /// Create a district over the region `(x0, y0)..=(x1, y1)` of a world (admin only)
+        #[ink(message)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn create_district(
+            &mut self,
+            world_id: u32,
+            x0: i32,
+            y0: i32,
+            x1: i32,
+            y1: i32,
+            treasury: AccountId,
+            share_bps: u16,
+        ) -> Result<u32> {
+            self.ensure_admin()?;
+            if x0 > x1 || y0 > y1 {
+                return Err(Error::InvalidRegion);
+            }
+            if share_bps > MAX_DISTRICT_SHARE_BPS {
+                return Err(Error::InvalidShare);
+            }
+            self.ensure_valid_recipient(treasury)?;
+
+            let district = District { world_id, x0, y0, x1, y1, treasury, share_bps };
+            let mut in_world = self.world_districts.get(world_id).unwrap_or_default();
+            if in_world.len() >= MAX_DISTRICTS_PER_WORLD as usize {
+                return Err(Error::TooManyDistricts);
+            }
+            let overlapping = in_world.iter().any(|&other| {
+                self.districts
+                    .get(other)
+                    .is_some_and(|other| other.overlaps(&district))
+            });
+            if overlapping {
+                return Err(Error::DistrictOverlap);
+            }
+
+            let district_id = self.district_count;
+            self.district_count = district_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.districts.insert(district_id, &district);
+            in_world.push(district_id);
+            self.world_districts.insert(world_id, &in_world);
+
+            self.env().emit_event(DistrictCreated {
+                district_id,
+                world_id,
+                treasury,
+                share_bps,
+            });
+
+            Ok(district_id)
+        }
+
+        /// Change a district's treasury or rental share (treasury only)
+        #[ink(message)]
+        pub fn configure_district(&mut self, district_id: u32, treasury: AccountId, share_bps: u16) -> Result<()> {
+            let mut district = self.districts.get(district_id).ok_or(Error::DistrictNotFound)?;
+            if district.treasury != self.env().caller() {
+                return Err(Error::NotTreasury);
+            }
+            if share_bps > MAX_DISTRICT_SHARE_BPS {
+                return Err(Error::InvalidShare);
+            }
+            self.ensure_valid_recipient(treasury)?;
+
+            district.treasury = treasury;
+            district.share_bps = share_bps;
+            self.districts.insert(district_id, &district);
+
+            self.env().emit_event(DistrictConfigured {
+                district_id,
+                treasury,
+                share_bps,
+            });
+
+            Ok(())
+        }
+
+        /// Get a district's council election settings
+        #[ink(message)]
+        pub fn council_config(&self, district_id: u32) -> Option<CouncilConfig> {
+            self.council_configs.get(district_id)
+        }
+
+        /// Get the council election running in a district
+        #[ink(message)]
+        pub fn council_election(&self, district_id: u32) -> Option<CouncilElection> {
+            self.council_elections.get(district_id)
+        }
+
+        /// Get a district's council while it is in office
+        #[ink(message)]
+        pub fn council(&self, district_id: u32) -> Option<Council> {
+            self.councils
+                .get(district_id)
+                .filter(|council| self.env().block_number() <= council.until)
+        }
+
+        /// Check whether an account sits on a district's council
+        #[ink(message)]
+        pub fn is_council_member(&self, district_id: u32, account: AccountId) -> bool {
+            self.council(district_id)
+                .is_some_and(|council| council.members.contains(&account))
+        }
+
+        /// Get the metadata URI a district's council published
+        #[ink(message)]
+        pub fn district_metadata(&self, district_id: u32) -> Option<String> {
+            self.district_metadata.get(district_id)
+        }
+
+        /// Get the entry rules applying to every parcel of a district
+        #[ink(message)]
+        pub fn district_gates(&self, district_id: u32) -> Vec<GateRule> {
+            self.district_gates.get(district_id).unwrap_or_default()
+        }
+
+        /// Set the seats and timing of a district's council elections (treasury only).
+        /// Elections already running keep the settings they started with.
+        #[ink(message)]
+        pub fn configure_council(&mut self, district_id: u32, config: CouncilConfig) -> Result<()> {
+            let district = self.districts.get(district_id).ok_or(Error::DistrictNotFound)?;
+            if district.treasury != self.env().caller() {
+                return Err(Error::NotTreasury);
+            }
+            if config.seats == 0
+                || config.seats > MAX_COUNCIL_SEATS
+                || config.nomination_blocks == 0
+                || config.voting_blocks == 0
+                || config.term_blocks == 0
+            {
+                return Err(Error::InvalidCouncilConfig);
+            }
+            self.council_configs.insert(district_id, &config);
+            Ok(())
+        }
+
+        /// Open a district's council election for nominations. Anyone can
+        /// start one once the previous council's term is over.
+        #[ink(message)]
+        pub fn start_council_election(&mut self, district_id: u32) -> Result<u32> {
+            let config = self.council_configs.get(district_id).ok_or(Error::CouncilNotConfigured)?;
+            if self.council_elections.contains(district_id) || self.council(district_id).is_some() {
+                return Err(Error::CouncilInOffice);
+            }
+
+            let now = self.env().block_number();
+            let nomination_end = now.checked_add(config.nomination_blocks).ok_or(Error::Overflow)?;
+            let voting_end = nomination_end.checked_add(config.voting_blocks).ok_or(Error::Overflow)?;
+            let round = self
+                .council_rounds
+                .get(district_id)
+                .unwrap_or(0)
+                .checked_add(1)
+                .ok_or(Error::Overflow)?;
+            self.council_rounds.insert(district_id, &round);
+            self.council_elections.insert(
+                district_id,
+                &CouncilElection {
+                    round,
+                    seats: config.seats,
+                    term_blocks: config.term_blocks,
+                    nomination_end,
+                    voting_end,
+                    candidates: Vec::new(),
+                },
+            );
+
+            self.env().emit_event(CouncilElectionStarted {
+                district_id,
+                round,
+                nomination_end,
+                voting_end,
+            });
+
+            Ok(round)
+        }
+
+        /// Stand for a district's council while nominations are open.
+        /// The caller must own `token_id`, a parcel or estate in the district.
+        #[ink(message)]
+        pub fn nominate(&mut self, district_id: u32, token_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+            let mut election = self.council_elections.get(district_id).ok_or(Error::NoElection)?;
+            if self.env().block_number() > election.nomination_end {
+                return Err(Error::WrongElectionPhase);
+            }
+            self.ensure_owner(token_id)?;
+            if self.parcels_in_district(district_id, token_id).is_empty() {
+                return Err(Error::NotInDistrict);
+            }
+            if election.candidates.iter().any(|candidate| candidate.account == caller) {
+                return Err(Error::AlreadyNominated);
+            }
+            if election.candidates.len() >= MAX_COUNCIL_CANDIDATES as usize {
+                return Err(Error::TooManyCandidates);
+            }
+
+            election.candidates.push(CouncilCandidate { account: caller, votes: 0 });
+            self.council_elections.insert(district_id, &election);
+
+            self.env().emit_event(CouncilCandidateNominated {
+                district_id,
+                candidate: caller,
+            });
+
+            Ok(())
+        }
+
+        /// Vote for up to one candidate per seat with a parcel or estate the
+        /// caller owns, once voting is open. Each of the token's parcels in the
+        /// district counts once per election, whoever holds it.
+        #[ink(message)]
+        pub fn vote_council(&mut self, district_id: u32, token_id: u32, candidates: Vec<AccountId>) -> Result<u32> {
+            let caller = self.env().caller();
+            let mut election = self.council_elections.get(district_id).ok_or(Error::NoElection)?;
+            let now = self.env().block_number();
+            if now <= election.nomination_end || now > election.voting_end {
+                return Err(Error::WrongElectionPhase);
+            }
+            self.ensure_owner(token_id)?;
+
+            if candidates.is_empty() || candidates.len() > usize::from(election.seats) {
+                return Err(Error::InvalidBallot);
+            }
+            for (i, account) in candidates.iter().enumerate() {
+                let repeated = candidates.iter().take(i).any(|seen| seen == account);
+                let standing = election.candidates.iter().any(|candidate| candidate.account == *account);
+                if repeated || !standing {
+                    return Err(Error::InvalidBallot);
+                }
+            }
+
+            let parcels = self.parcels_in_district(district_id, token_id);
+            if parcels.is_empty() {
+                return Err(Error::NotInDistrict);
+            }
+            if parcels
+                .iter()
+                .any(|&parcel| self.council_votes.get(parcel) == Some(election.round))
+            {
+                return Err(Error::AlreadyVoted);
+            }
+            let weight = u32::try_from(parcels.len()).map_err(|_| Error::Overflow)?;
+
+            for candidate in election.candidates.iter_mut() {
+                if candidates.contains(&candidate.account) {
+                    candidate.votes = candidate.votes.checked_add(weight).ok_or(Error::Overflow)?;
+                }
+            }
+            for &parcel in &parcels {
+                self.council_votes.insert(parcel, &election.round);
+            }
+            self.council_elections.insert(district_id, &election);
+
+            self.env().emit_event(CouncilVoteCast {
+                district_id,
+                voter: caller,
+                token_id,
+                candidates,
+                weight,
+            });
+
+            Ok(weight)
+        }
+
+        /// Close a district's election once voting is over and seat the
+        /// candidates with the most votes. Ties go to the earlier nomination
+        /// and candidates without votes aren't seated.
+        #[ink(message)]
+        pub fn close_council_election(&mut self, district_id: u32) -> Result<Vec<AccountId>> {
+            let mut election = self.council_elections.get(district_id).ok_or(Error::NoElection)?;
+            let now = self.env().block_number();
+            if now <= election.voting_end {
+                return Err(Error::WrongElectionPhase);
+            }
+
+            // Stable sort, so equal votes keep nomination order
+            election.candidates.sort_by_key(|candidate| core::cmp::Reverse(candidate.votes));
+            let members: Vec<AccountId> = election
+                .candidates
+                .iter()
+                .filter(|candidate| candidate.votes > 0)
+                .take(usize::from(election.seats))
+                .map(|candidate| candidate.account)
+                .collect();
+            // The term starts in the current block
+            let until = now.saturating_add(election.term_blocks).saturating_sub(1);
+
+            self.council_elections.remove(district_id);
+            self.councils.insert(
+                district_id,
+                &Council {
+                    members: members.clone(),
+                    until,
+                },
+            );
+
+            self.env().emit_event(CouncilElected {
+                district_id,
+                members: members.clone(),
+                until,
+            });
+
+            Ok(members)
+        }
+
+        /// Publish a district's metadata URI (council members only)
+        #[ink(message)]
+        pub fn set_district_metadata(&mut self, district_id: u32, uri: String) -> Result<()> {
+            self.ensure_council_member(district_id)?;
+            self.district_metadata.insert(district_id, &uri);
+
+            self.env().emit_event(DistrictMetadataSet { district_id, uri });
+
+            Ok(())
+        }
+
+        /// Replace the entry rules applying to every parcel of a district
+        /// (council members only). Visitors must satisfy these on top of the
+        /// parcel's own rules; an empty list removes them.
+        #[ink(message)]
+        pub fn set_district_gates(&mut self, district_id: u32, gates: Vec<GateRule>) -> Result<()> {
+            self.ensure_council_member(district_id)?;
+            if gates.len() > MAX_GATE_RULES as usize {
+                return Err(Error::TooManyGateRules);
+            }
+
+            if gates.is_empty() {
+                self.district_gates.remove(district_id);
+            } else {
+                self.district_gates.insert(district_id, &gates);
+            }
+
+            self.env().emit_event(DistrictGatesSet { district_id, gates });
+
+            Ok(())
+        }
+
+        /// Get a parcel's open rental offer
+        #[cfg(feature = "rental")]
+        #[ink(message)]
+        pub fn rental_offer(&self, parcel_id: u32) -> Option<RentalOffer> {
+            self.rental_offers.get(parcel_id)
+        }
+
+        /// Get a parcel's active rental
+        #[cfg(feature = "rental")]
+        #[ink(message)]
+        pub fn rental_of(&self, parcel_id: u32) -> Option<Rental> {
+            self.rentals
+                .get(parcel_id)
+                .filter(|rental| self.env().block_number() <= rental.until)
+        }
+
+        /// Get the rental income a district can withdraw
+        #[cfg(feature = "rental")]
+        #[ink(message)]
+        pub fn district_revenue(&self, district_id: u32) -> Balance {
+            self.district_revenue.get(district_id).unwrap_or(0)
+        }
+
+        /// Offer a parcel for rent for up to `max_blocks` at a time (owner only).
+        /// The offer is dropped when the parcel changes hands.
+        #[cfg(feature = "rental")]
+        #[ink(message)]
+        pub fn offer_rental(&mut self, parcel_id: u32, price_per_block: Balance, max_blocks: BlockNumber) -> Result<()> {
+            self.ensure_owner(parcel_id)?;
+            if max_blocks == 0 {
+                return Err(Error::InvalidRentalTerm);
+            }
+
+            self.rental_offers.insert(parcel_id, &RentalOffer { price_per_block, max_blocks });
+
+            self.env().emit_event(RentalOffered {
+                parcel_id,
+                price_per_block,
+                max_blocks,
+            });
+
+            Ok(())
+        }
+
+        /// Withdraw a parcel's rental offer (owner only); an active rental runs its course
+        #[cfg(feature = "rental")]
+        #[ink(message)]
+        pub fn cancel_rental_offer(&mut self, parcel_id: u32) -> Result<()> {
+            self.ensure_owner(parcel_id)?;
+            if self.rental_offers.take(parcel_id).is_none() {
+                return Err(Error::NotForRent);
+            }
+            self.env().emit_event(RentalOfferCancelled { parcel_id });
+            Ok(())
+        }
+
+        /// Rent a parcel for `blocks` blocks, paying exactly `price_per_block * blocks`.
+        /// The parcel's district gets its share and the owner the rest.
+        /// Returns the last block of the rental.
+        #[cfg(feature = "rental")]
+        #[ink(message, payable)]
+        pub fn rent(&mut self, parcel_id: u32, blocks: BlockNumber) -> Result<BlockNumber> {
+            let renter = self.env().caller();
+            let owner = self.ensure_exists(parcel_id)?;
+            let offer = self.rental_offers.get(parcel_id).ok_or(Error::NotForRent)?;
+            if renter == owner {
+                return Err(Error::SelfTransfer);
+            }
+            if self.rental_of(parcel_id).is_some() {
+                return Err(Error::Rented);
+            }
+            if blocks == 0 || blocks > offer.max_blocks {
+                return Err(Error::InvalidRentalTerm);
+            }
+            let price = offer
+                .price_per_block
+                .checked_mul(Balance::from(blocks))
+                .ok_or(Error::Overflow)?;
+            if self.env().transferred_value() != price {
+                return Err(Error::WrongPayment);
+            }
+
+            // The rental starts in the current block
+            let until = self
+                .env()
+                .block_number()
+                .saturating_add(blocks)
+                .saturating_sub(1);
+            self.rentals.insert(parcel_id, &Rental { renter, until });
+
+            let mut district_share = 0;
+            if let Some(district_id) = self.district_of(parcel_id) {
+                let district = self.districts.get(district_id).ok_or(Error::DistrictNotFound)?;
+                district_share = royalty_amount(price, district.share_bps);
+                let revenue = self
+                    .district_revenue(district_id)
+                    .checked_add(district_share)
+                    .ok_or(Error::Overflow)?;
+                self.district_revenue.insert(district_id, &revenue);
+            }
+            let owner_share = price.saturating_sub(district_share);
+            if owner_share > 0 && self.env().transfer(owner, owner_share).is_err() {
+                return Err(Error::TransferFailed);
+            }
+
+            self.env().emit_event(ParcelRented {
+                parcel_id,
+                renter,
+                until,
+                price,
+                district_share,
+            });
+
+            Ok(until)
+        }
+
+        /// Pay a district's accumulated rental income to its treasury (treasury only)
+        #[cfg(feature = "rental")]
+        #[ink(message)]
+        pub fn withdraw_district_revenue(&mut self, district_id: u32) -> Result<Balance> {
+            let district = self.districts.get(district_id).ok_or(Error::DistrictNotFound)?;
+            if district.treasury != self.env().caller() {
+                return Err(Error::NotTreasury);
+            }
+
+            let amount = self.district_revenue.take(district_id).unwrap_or(0);
+            if amount > 0 && self.env().transfer(district.treasury, amount).is_err() {
+                return Err(Error::TransferFailed);
+            }
+
+            self.env().emit_event(DistrictRevenueWithdrawn {
+                district_id,
+                treasury: district.treasury,
+                amount,
+            });
+
+            Ok(amount)
+        }
+
+        /// Check whether an account may verify published worlds
+        #[ink(message)]
+        pub fn is_curator(&self, account: AccountId) -> bool {
+            self.curators.contains(account)
+        }
+
+        /// Grant or revoke curator rights (admin only)
+        #[ink(message)]
+        pub fn set_curator(&mut self, account: AccountId, allowed: bool) -> Result<()> {
+            self.ensure_admin()?;
+            if allowed {
+                self.curators.insert(account, &());
+            } else {
+                self.curators.remove(account);
+            }
+            Ok(())
+        }
+
+        /// Get the deposit required to reserve a world name
+        #[ink(message)]
+        pub fn name_deposit(&self) -> Balance {
+            self.name_deposit
+        }
+
+        /// Change the deposit for new name reservations (admin only)
+        #[ink(message)]
+        pub fn set_name_deposit(&mut self, deposit: Balance) -> Result<()> {
+            self.ensure_admin()?;
+            self.name_deposit = deposit;
+            Ok(())
+        }
+
+        /// Get a world's reserved name
+        #[ink(message)]
+        pub fn world_name(&self, world_id: u32) -> Option<WorldName> {
+            self.world_names.get(world_id)
+        }
+
+        /// Get the world a name is reserved for
+        #[ink(message)]
+        pub fn world_by_name(&self, name: String) -> Option<u32> {
+            self.world_by_name.get(name)
+        }
+
+        /// Reserve a name for a world, paying exactly `name_deposit`. The deposit
+        /// comes back once the world publishes content: when the caller commits
+        /// a scene snapshot in it, or a curator verifies it.
+        #[ink(message, payable)]
+        pub fn reserve_world_name(&mut self, world_id: u32, name: String) -> Result<()> {
+            let owner = self.env().caller();
+            let deposit = self.env().transferred_value();
+            if name.is_empty() || name.len() > MAX_WORLD_NAME_LEN {
+                return Err(Error::InvalidName);
+            }
+            if self.world_names.contains(world_id) || self.world_by_name.contains(&name) {
+                return Err(Error::NameTaken);
+            }
+            if deposit != self.name_deposit {
+                return Err(Error::WrongPayment);
+            }
+
+            self.world_by_name.insert(&name, &world_id);
+            self.world_names.insert(
+                world_id,
+                &WorldName {
+                    name: name.clone(),
+                    owner,
+                    deposit,
+                    published: false,
+                },
+            );
+
+            self.env().emit_event(WorldNameReserved {
+                world_id,
+                owner,
+                name,
+                deposit,
+            });
+
+            Ok(())
+        }
+
+        /// Confirm a world publishes content and return its name deposit (curator or admin)
+        #[ink(message)]
+        pub fn verify_world(&mut self, world_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+            if caller != self.admin && !self.is_curator(caller) {
+                return Err(Error::NotCurator);
+            }
+            let world = self.world_names.get(world_id).ok_or(Error::NameNotReserved)?;
+            if world.published {
+                return Err(Error::AlreadyPublished);
+            }
+            self.publish_world(world_id, world)
+        }
+
+        /// Take back the name of a world that never published content; the
+        /// deposit is forfeited to the admin (admin only)
+        #[ink(message)]
+        pub fn reclaim_world_name(&mut self, world_id: u32) -> Result<()> {
+            self.ensure_admin()?;
+            let world = self.world_names.get(world_id).ok_or(Error::NameNotReserved)?;
+            if world.published {
+                return Err(Error::AlreadyPublished);
+            }
+
+            if world.deposit > 0 && self.env().transfer(self.admin, world.deposit).is_err() {
+                return Err(Error::TransferFailed);
+            }
+            self.world_names.remove(world_id);
+            self.world_by_name.remove(&world.name);
+
+            self.env().emit_event(WorldNameReclaimed {
+                world_id,
+                owner: world.owner,
+                name: world.name,
+                deposit: world.deposit,
+            });
+
+            Ok(())
+        }
+
+        /// Grant an account scene-edit rights on a parcel (parcel owner only).
+        /// Builders are cleared whenever the parcel changes hands.
+        #[ink(message)]
+        pub fn add_builder(&mut self, parcel_id: u32, account: AccountId) -> Result<()> {
+            self.ensure_owner(parcel_id)?;
+
+            let mut builders = self.builders_of(parcel_id);
+            if builders.contains(&account) {
+                return Err(Error::BuilderAlreadyAdded);
+            }
+            if builders.len() >= MAX_BUILDERS as usize {
+                return Err(Error::TooManyBuilders);
+            }
+            builders.push(account);
+            self.parcel_builders.insert(parcel_id, &builders);
+
+            self.env().emit_event(BuilderAdded {
+                parcel_id,
+                builder: account,
+            });
+
+            Ok(())
+        }
+
+        /// Revoke an account's scene-edit rights on a parcel (parcel owner only)
+        #[ink(message)]
+        pub fn remove_builder(&mut self, parcel_id: u32, account: AccountId) -> Result<()> {
+            self.ensure_owner(parcel_id)?;
+
+            let mut builders = self.builders_of(parcel_id);
+            let index = builders
+                .iter()
+                .position(|b| *b == account)
+                .ok_or(Error::BuilderNotFound)?;
+            builders.swap_remove(index);
+            if builders.is_empty() {
+                self.parcel_builders.remove(parcel_id);
+            } else {
+                self.parcel_builders.insert(parcel_id, &builders);
+            }
+
+            self.env().emit_event(BuilderRemoved {
+                parcel_id,
+                builder: account,
+            });
+
+            Ok(())
+        }
+
+        /// Require holdings of a collection to enter a parcel (parcel owner only).
+        /// Setting `min_balance` to zero removes the rule for that collection.
+        /// With `count_linked`, tokens of the entrant's linked accounts count too.
+        #[ink(message)]
+        pub fn set_gate(
+            &mut self,
+            parcel_id: u32,
+            required_collection: AccountId,
+            min_balance: u32,
+            count_linked: bool,
+        ) -> Result<()> {
+            self.ensure_owner(parcel_id)?;
+
+            let mut rules = self.gates_of(parcel_id);
+            rules.retain(|rule| rule.collection != required_collection);
+            if min_balance > 0 {
+                if rules.len() >= MAX_GATE_RULES as usize {
+                    return Err(Error::TooManyGateRules);
+                }
+                rules.push(GateRule {
+                    collection: required_collection,
+                    min_balance,
+                    count_linked,
+                });
+            }
+
+            if rules.is_empty() {
+                self.parcel_gates.remove(parcel_id);
+            } else {
+                self.parcel_gates.insert(parcel_id, &rules);
+            }
+
+            self.env().emit_event(GateSet {
+                parcel_id,
+                collection: required_collection,
+                min_balance,
+                count_linked,
+            });
+
+            Ok(())
+        }
+
+        /// Open a portal from a parcel to a parcel in another world (parcel owner only)
+        #[ink(message)]
+        pub fn create_portal(&mut self, from_parcel: u32, to_world: u32, to_parcel: u32) -> Result<()> {
+            self.ensure_owner(from_parcel)?;
+
+            let portal = Portal { to_world, to_parcel };
+            let mut portals = self.portals_of(from_parcel);
+            if portals.contains(&portal) {
+                return Err(Error::PortalExists);
+            }
+            if portals.len() >= MAX_PORTALS as usize {
+                return Err(Error::TooManyPortals);
+            }
+            portals.push(portal);
+            self.parcel_portals.insert(from_parcel, &portals);
+
+            self.env().emit_event(PortalCreated {
+                from_parcel,
+                to_world,
+                to_parcel,
+            });
+
+            Ok(())
+        }
+
+        /// Close a portal from a parcel (parcel owner only)
+        #[ink(message)]
+        pub fn remove_portal(&mut self, from_parcel: u32, to_world: u32, to_parcel: u32) -> Result<()> {
+            self.ensure_owner(from_parcel)?;
+
+            let portal = Portal { to_world, to_parcel };
+            let mut portals = self.portals_of(from_parcel);
+            let index = portals
+                .iter()
+                .position(|p| *p == portal)
+                .ok_or(Error::PortalNotFound)?;
+            portals.swap_remove(index);
+            if portals.is_empty() {
+                self.parcel_portals.remove(from_parcel);
+            } else {
+                self.parcel_portals.insert(from_parcel, &portals);
+            }
+
+            self.env().emit_event(PortalRemoved {
+                from_parcel,
+                to_world,
+                to_parcel,
+            });
+
+            Ok(())
+        }
+
+        /// Anchor a prop NFT into a parcel's scene (parcel owner, builder or renter).
+        /// Ownership of the item itself is checked by clients against its collection.
+        #[ink(message)]
+        pub fn place_in_scene(
+            &mut self,
+            parcel_id: u32,
+            collection: AccountId,
+            item_token_id: u32,
+            transform: Transform,
+        ) -> Result<()> {
+            self.ensure_can_edit_scene(parcel_id)?;
+
+            let item = ItemRef {
+                collection,
+                token_id: item_token_id,
+            };
+            if self.item_parcel.contains(item) {
+                return Err(Error::ItemAlreadyPlaced);
+            }
+
+            let mut items = self.scene_items.get(parcel_id).unwrap_or_default();
+            if items.len() >= MAX_SCENE_ITEMS as usize {
+                return Err(Error::SceneFull);
+            }
+            items.push(SceneItem { item, transform });
+            self.scene_items.insert(parcel_id, &items);
+            self.item_parcel.insert(item, &parcel_id);
+
+            self.env().emit_event(ItemPlaced {
+                parcel_id,
+                item,
+                transform,
+            });
+
+            Ok(())
+        }
+
+        /// Remove an anchored item from a parcel's scene (parcel owner, builder or renter)
+        #[ink(message)]
+        pub fn remove_from_scene(
+            &mut self,
+            parcel_id: u32,
+            collection: AccountId,
+            item_token_id: u32,
+        ) -> Result<()> {
+            self.ensure_can_edit_scene(parcel_id)?;
+
+            let item = ItemRef {
+                collection,
+                token_id: item_token_id,
+            };
+            if self.item_parcel.get(item) != Some(parcel_id) {
+                return Err(Error::ItemNotInScene);
+            }
+
+            self.detach_item(parcel_id, item);
+            Ok(())
+        }
+
+        /// Record the hash of a parcel's full serialized scene (parcel owner, builder or renter)
+        #[ink(message)]
+        pub fn commit_scene_snapshot(&mut self, parcel_id: u32, snapshot_hash: Hash) -> Result<u32> {
+            self.ensure_can_edit_scene(parcel_id)?;
+
+            let caller = self.env().caller();
+            let index = self.snapshot_count(parcel_id);
+            let count = index.checked_add(1).ok_or(Error::Overflow)?;
+            self.scene_snapshots.insert(
+                (parcel_id, index),
+                &SceneSnapshot {
+                    snapshot_hash,
+                    block: self.env().block_number(),
+                    committer: caller,
+                },
+            );
+            self.snapshot_count.insert(parcel_id, &count);
+
+            self.env().emit_event(SceneSnapshotCommitted {
+                parcel_id,
+                snapshot_hash,
+                index,
+                committer: caller,
+            });
+
+            // A snapshot by the name's owner shows the world publishes content
+            if let Some(world_id) = self.location_of(parcel_id).map(|c| c.world_id) {
+                if let Some(world) = self.world_names.get(world_id) {
+                    if !world.published && world.owner == caller {
+                        self.publish_world(world_id, world)?;
+                    }
+                }
+            }
+
+            Ok(index)
+        }
+
+        /// Get a parcel's coordinates, or those of an estate's first parcel
+        fn location_of(&self, parcel_id: u32) -> Option<Coordinates> {
+            match self.estate_parcels.get(parcel_id) {
+                Some(parcels) => self.parcel_coordinates.get(*parcels.first()?),
+                None => self.parcel_coordinates.get(parcel_id),
+            }
+        }
+
+        /// Get the parcels of a parcel or estate that lie inside a district
+        fn parcels_in_district(&self, district_id: u32, token_id: u32) -> Vec<u32> {
+            let district = match self.districts.get(district_id) {
+                Some(district) => district,
+                None => return Vec::new(),
+            };
+            let mut parcels = self.estate_parcels.get(token_id).unwrap_or_else(|| vec![token_id]);
+            parcels.retain(|&parcel| {
+                self.parcel_coordinates
+                    .get(parcel)
+                    .is_some_and(|coordinates| district.contains(&coordinates))
+            });
+            parcels
+        }
+
+        /// Check that the caller sits on a district's council
+        fn ensure_council_member(&self, district_id: u32) -> Result<()> {
+            if !self.is_council_member(district_id, self.env().caller()) {
+                return Err(Error::NotCouncilMember);
+            }
+            Ok(())
+        }
+
+        /// Check whether an account satisfies a set of entry rules: any one
+        /// of them, or none if the set is empty
+        fn passes_gates(&self, account: AccountId, rules: &[GateRule]) -> bool {
+            rules.is_empty()
+                || rules
+                    .iter()
+                    .any(|rule| self.gate_balance(rule, account) >= rule.min_balance)
+        }
+
+        /// Get the balance a gate rule counts for an account: its own, plus
+        /// its linked accounts' if the rule says so
+        fn gate_balance(&self, rule: &GateRule, account: AccountId) -> u32 {
+            let own = self.balance_in(rule.collection, account);
+            if !rule.count_linked {
+                return own;
+            }
+            self.linked_in(rule.collection, account)
+                .into_iter()
+                .take(MAX_LINKED_ACCOUNTS)
+                .fold(own, |balance, linked| balance.saturating_add(self.balance_in(rule.collection, linked)))
+        }
+
+        /// Mark a world as published and return its name deposit
+        fn publish_world(&mut self, world_id: u32, mut world: WorldName) -> Result<()> {
+            if world.deposit > 0 && self.env().transfer(world.owner, world.deposit).is_err() {
+                return Err(Error::TransferFailed);
+            }
+            world.published = true;
+            self.world_names.insert(world_id, &world);
+
+            self.env().emit_event(WorldPublished {
+                world_id,
+                owner: world.owner,
+                deposit: world.deposit,
+            });
+
+            Ok(())
+        }
+
+        /// Get the owner of a token, failing if it doesn't exist
+        fn ensure_exists(&self, token_id: u32) -> Result<AccountId> {
+            self.token_owner.get(token_id).ok_or(Error::TokenNotFound)
+        }
+
+        /// Check that the caller may edit a parcel's scene
+        fn ensure_can_edit_scene(&self, parcel_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+            let owner = self.ensure_exists(parcel_id)?;
+            if owner != caller && !self.is_builder(parcel_id, caller) && !self.is_renter(parcel_id, caller) {
+                return Err(Error::NotOwner);
+            }
+            Ok(())
+        }
+
+        /// Get a parcel's active renter, if any
+        fn active_renter(&self, parcel_id: u32) -> Option<AccountId> {
+            #[cfg(feature = "rental")]
+            {
+                self.rental_of(parcel_id).map(|rental| rental.renter)
+            }
+            #[cfg(not(feature = "rental"))]
+            {
+                let _ = parcel_id;
+                None
+            }
+        }
+
+        /// Check whether `account` is renting a parcel right now
+        fn is_renter(&self, parcel_id: u32, account: AccountId) -> bool {
+            self.active_renter(parcel_id) == Some(account)
+        }
+
+        /// Check that a parcel has no active rental, so it can be merged or split
+        fn ensure_not_rented(&self, parcel_id: u32) -> Result<()> {
+            if self.active_renter(parcel_id).is_some() {
+                return Err(Error::Rented);
+            }
+            Ok(())
+        }
+
+        /// Check that a parcel isn't locked as a governance sacrifice
+        fn ensure_unlocked(&self, parcel_id: u32) -> Result<()> {
+            if self.locked_until(parcel_id).is_some() {
+                return Err(Error::Locked);
+            }
+            Ok(())
+        }
+
+        /// Check that the caller owns a token
+        fn ensure_owner(&self, token_id: u32) -> Result<()> {
+            if self.ensure_exists(token_id)? != self.env().caller() {
+                return Err(Error::NotOwner);
+            }
+            Ok(())
+        }
+
+        /// Accounts a collection's `AccountLinks` registry links to `account`;
+        /// failed calls, and collections without a registry, give none
+        fn linked_in(&self, collection: AccountId, account: AccountId) -> Vec<AccountId> {
+            if collection == self.env().account_id() {
+                return Vec::new();
+            }
+
+            let registry: ink::contract_ref!(AccountLinks) = collection.into();
+            registry
+                .call()
+                .linked_accounts(account)
+                .try_invoke()
+                .ok()
+                .and_then(|result| result.ok())
+                .unwrap_or_default()
+        }
+
+        /// Balance of `account` in a `PSP34Core` collection; failed calls count as zero
+        fn balance_in(&self, collection: AccountId, account: AccountId) -> u32 {
+            if collection == self.env().account_id() {
+                return self.balance_of(account);
+            }
+
+            let collection: ink::contract_ref!(PSP34Core) = collection.into();
+            collection
+                .call()
+                .balance_of(account)
+                .try_invoke()
+                .ok()
+                .and_then(|result| result.ok())
+                .unwrap_or(0)
+        }
+
+        /// Take an item out of a parcel's scene
+        fn detach_item(&mut self, parcel_id: u32, item: ItemRef) {
+            let mut items = self.scene_items.get(parcel_id).unwrap_or_default();
+            items.retain(|placed| placed.item != item);
+            if items.is_empty() {
+                self.scene_items.remove(parcel_id);
+            } else {
+                self.scene_items.insert(parcel_id, &items);
+            }
+            self.item_parcel.remove(item);
+
+            self.env().emit_event(ItemRemoved { parcel_id, item });
+        }
+
+        /// Check that the coordinates form a single edge-connected area
+        fn is_connected(coordinates: &[Coordinates]) -> bool {
+            let mut reached = Vec::with_capacity(coordinates.len());
+            let mut frontier = Vec::new();
+            reached.push(0);
+            frontier.push(0);
+
+            while let Some(current) = frontier.pop().and_then(|i| coordinates.get(i)) {
+                for (j, c) in coordinates.iter().enumerate() {
+                    if !reached.contains(&j) && current.is_adjacent(c) {
+                        reached.push(j);
+                        frontier.push(j);
+                    }
+                }
+            }
+
+            reached.len() == coordinates.len()
+        }
+
+        /// Only the admin may call
+        fn ensure_admin(&self) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAdmin);
+            }
+            Ok(())
+        }
+
+        /// Reject recipients that could never move the parcel again
+        fn ensure_valid_recipient(&self, to: AccountId) -> Result<()> {
+            if !is_valid_recipient(&to, &self.env().account_id()) {
+                return Err(Error::InvalidRecipient);
+            }
+            Ok(())
+        }
+
+        /// Mint a parcel at a coordinate already known to be free
+        fn mint_at(&mut self, to: AccountId, coordinates: Coordinates) -> Result<u32> {
+            let token_id = self.mint_token(to)?;
+
+            self.parcel_coordinates.insert(token_id, &coordinates);
+            self.parcel_by_coordinates.insert(coordinates, &token_id);
+
+            self.env().emit_event(ParcelMinted {
+                token_id,
+                world_id: coordinates.world_id,
+                x: coordinates.x,
+                y: coordinates.y,
+            });
+
+            Ok(token_id)
+        }
+
+        /// Allocate a new token ID and assign it to `to`
+        fn mint_token(&mut self, to: AccountId) -> Result<u32> {
+            let token_id = self.next_token_id;
+            self.next_token_id = token_id.checked_add(1).ok_or(Error::Overflow)?;
+
+            self.add_token_to(to, token_id)?;
+
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(to),
+                token_id,
+            });
+
+            Ok(token_id)
+        }
+
+        /// Remove a token from circulation (coordinate records are kept)
+        fn burn(&mut self, owner: AccountId, token_id: u32) {
+            self.remove_token_from(owner, token_id);
+            self.token_owner.remove(token_id);
+
+            self.env().emit_event(Transfer {
+                from: Some(owner),
+                to: None,
+                token_id,
+            });
+        }
+
+        /// Assign ownership of a parcel and update the owner's bookkeeping
+        fn add_token_to(&mut self, to: AccountId, token_id: u32) -> Result<()> {
+            OwnerIndex {
+                balances: &mut self.balances,
+                tokens: &mut self.owned_token_by_index,
+                positions: &mut self.owned_token_index,
+            }
+            .push(to, token_id)
+            .ok_or(Error::Overflow)?;
+            self.token_owner.insert(token_id, &to);
+            self.checkpoint_balance(to);
+            let block = self.env().block_number();
+            self.delegation().move_tokens(None, Some(to), 1, block).ok_or(Error::Overflow)
+        }
+
+        /// Drop a parcel from its owner's bookkeeping
+        fn remove_token_from(&mut self, owner: AccountId, token_id: u32) {
+            self.parcel_builders.remove(token_id);
+            #[cfg(feature = "rental")]
+            self.rental_offers.remove(token_id);
+            OwnerIndex {
+                balances: &mut self.balances,
+                tokens: &mut self.owned_token_by_index,
+                positions: &mut self.owned_token_index,
+            }
+            .remove(owner, token_id);
+            self.checkpoint_balance(owner);
+            let block = self.env().block_number();
+            // Only lowers voting power, which can't fail
+            let _ = self.delegation().move_tokens(Some(owner), None, 1, block);
+        }
+
+        /// Point the caller's votes at `delegate`, or back at itself with `None`
+        fn set_delegate(&mut self, delegate: Option<AccountId>) -> Result<()> {
+            let delegator = self.env().caller();
+            let balance = self.balances.get(delegator).unwrap_or(0);
+            let block = self.env().block_number();
+            let mut delegation = self.delegation();
+            let from_delegate = delegation.representative(delegator);
+            delegation
+                .delegate(delegator, delegate, balance, block)
+                .ok_or(Error::Overflow)?;
+
+            self.env().emit_event(DelegateChanged {
+                delegator,
+                from_delegate,
+                to_delegate: delegate.unwrap_or(delegator),
+            });
+            Ok(())
+        }
+
+        /// Borrow the delegates and vote checkpoints
+        fn delegation(&mut self) -> Delegation<'_, impl StorageKey, impl StorageKey, impl StorageKey> {
+            Delegation {
+                delegates: &mut self.delegates,
+                votes: Checkpoints {
+                    checkpoints: &mut self.vote_checkpoints,
+                    counts: &mut self.vote_checkpoint_count,
+                },
+            }
+        }
+
+        /// Record an owner's current parcel count in its history
+        fn checkpoint_balance(&mut self, owner: AccountId) {
+            let balance = self.balances.get(owner).unwrap_or(0);
+            let block = self.env().block_number();
+            Checkpoints {
+                checkpoints: &mut self.balance_checkpoints,
+                counts: &mut self.balance_checkpoint_count,
+            }
+            .record(owner, block, balance);
+        }
+    }
+
+    impl PSP34Core for VrLand {
+        /// Get the balance of an account
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u32 {
+            self.balances.get(owner).unwrap_or(0)
+        }
+
+        /// Get the owner of a parcel
+        #[ink(message)]
+        fn owner_of(&self, token_id: u32) -> Option<AccountId> {
+            self.token_owner.get(token_id)
+        }
+
+        /// Check whether a parcel or estate currently exists
+        #[ink(message)]
+        fn exists(&self, token_id: u32) -> bool {
+            self.token_owner.contains(token_id)
+        }
+    }
+
+    impl BalanceHistory for VrLand {
+        /// Get the number of parcels and estates an account held at the end of `block`
+        #[ink(message)]
+        fn balance_at(&self, account: AccountId, block: BlockNumber) -> u32 {
+            checkpoints::balance_at(&self.balance_checkpoints, &self.balance_checkpoint_count, account, block)
+        }
+    }
+
+    impl Votes for VrLand {
+        /// Get the voting power of an account at the end of `block`
+        #[ink(message)]
+        fn votes_at(&self, account: AccountId, block: BlockNumber) -> u32 {
+            checkpoints::balance_at(&self.vote_checkpoints, &self.vote_checkpoint_count, account, block)
+        }
+
+        /// Get the account an owner delegated its votes to
+        #[ink(message)]
+        fn delegate_of(&self, account: AccountId) -> Option<AccountId> {
+            self.delegates.get(account)
+        }
+    }
+
+    impl Enumerable for VrLand {
+        /// Get all parcels owned by an address
+        #[ink(message)]
+        fn tokens_of_owner(&self, owner: AccountId) -> Vec<u32> {
+            (0..self.balance_of(owner))
+                .filter_map(|index| self.owned_token_by_index.get((owner, index)))
+                .collect()
+        }
+
+        /// Get the parcel at `index` of an owner's list (`index < balance_of(owner)`)
+        #[ink(message)]
+        fn token_of_owner_by_index(&self, owner: AccountId, index: u32) -> Option<u32> {
+            self.owned_token_by_index.get((owner, index))
+        }
+    }
+
+    impl SceneContainer for VrLand {
+        /// Get the parcel an item is anchored in
+        #[ink(message)]
+        fn placement_of(&self, item: ItemRef) -> Option<u32> {
+            self.item_parcel.get(item)
+        }
+
+        /// Check whether an account may enter a parcel.
+        /// The owner and builders always may; otherwise an ungated parcel is open
+        /// and a gated one admits holders satisfying any of its rules. The
+        /// district's rules, if any, must be satisfied as well.
+        #[ink(message)]
+        fn can_enter(&self, account: AccountId, parcel_id: u32) -> bool {
+            let owner = match self.token_owner.get(parcel_id) {
+                Some(owner) => owner,
+                None => return false,
+            };
+            if owner == account || self.is_builder(parcel_id, account) {
+                return true;
+            }
+
+            let district_gates = self
+                .district_of(parcel_id)
+                .map(|district_id| self.district_gates(district_id))
+                .unwrap_or_default();
+            self.passes_gates(account, &self.gates_of(parcel_id)) && self.passes_gates(account, &district_gates)
+        }
+    }
+
+    impl TransferHook for VrLand {
+        /// Parcels never block item transfers
+        #[ink(message)]
+        fn before_transfer(&mut self, _from: Option<AccountId>, _to: Option<AccountId>, _token_id: u32) -> bool {
+            true
+        }
+
+        /// Detach an item from its scene once its collection reports it changed
+        /// hands, so a sold prop doesn't stay anchored in the seller's parcel
+        #[ink(message)]
+        fn after_transfer(&mut self, _from: Option<AccountId>, _to: Option<AccountId>, token_id: u32) {
+            let item = ItemRef {
+                collection: self.env().caller(),
+                token_id,
+            };
+            if let Some(parcel_id) = self.item_parcel.get(item) {
+                self.detach_item(parcel_id, item);
+            }
+        }
+    }
+
+    impl Default for VrLand {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    /// Unit tests
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        fn new_land() -> VrLand {
+            // The off-chain contract address defaults to alice, who the tests mint to
+            test::set_callee::<DefaultEnvironment>(test::default_accounts::<DefaultEnvironment>().django);
+            VrLand::new()
+        }
+
+        #[ink::test]
+        fn mint_parcel_records_coordinates() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+
+            let token_id = land.mint_parcel(accounts.bob, 1, -3, 7).unwrap();
+
+            assert_eq!(land.owner_of(token_id), Some(accounts.bob));
+            assert_eq!(land.parcel_at(1, -3, 7), Some(token_id));
+            assert_eq!(land.parcel_at(2, -3, 7), None);
+            assert_eq!(
+                land.coordinates_of(token_id),
+                Some(Coordinates { world_id: 1, x: -3, y: 7 })
+            );
+        }
+
+        #[ink::test]
+        fn coordinates_are_unique() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+
+            land.mint_parcel(accounts.bob, 1, 0, 0).unwrap();
+            assert_eq!(land.mint_parcel(accounts.charlie, 1, 0, 0), Err(Error::CoordinatesTaken));
+            assert_eq!(land.balance_of(accounts.charlie), 0);
+        }
+
+        #[ink::test]
+        fn only_admin_can_mint() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(land.mint_parcel(accounts.bob, 1, 0, 0), Err(Error::NotAdmin));
+        }
+
+        #[ink::test]
+        fn mint_region_is_all_or_nothing() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+
+            let minted = land.mint_region(accounts.bob, 1, 0, 0, 2, 1).unwrap();
+            assert_eq!(minted.len(), 6);
+            assert_eq!(land.balance_of(accounts.bob), 6);
+            assert_eq!(land.parcel_at(1, 2, 1), minted.last().copied());
+
+            // Overlaps (2, 1), so nothing new is minted
+            assert_eq!(
+                land.mint_region(accounts.charlie, 1, 2, 1, 3, 2),
+                Err(Error::CoordinatesTaken)
+            );
+            assert_eq!(land.parcel_at(1, 3, 2), None);
+
+            assert_eq!(land.mint_region(accounts.bob, 1, 5, 5, 4, 4), Err(Error::InvalidRegion));
+            assert_eq!(land.mint_region(accounts.bob, 1, 0, 10, 10, 20), Err(Error::RegionTooLarge));
+        }
+
+        #[ink::test]
+        fn transfer_works() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let token_id = land.mint_parcel(accounts.alice, 1, 0, 0).unwrap();
+
+            assert_eq!(land.transfer(accounts.bob, 99), Err(Error::TokenNotFound));
+            assert_eq!(land.transfer(accounts.alice, token_id), Err(Error::SelfTransfer));
+            assert_eq!(land.transfer(AccountId::from([0u8; 32]), token_id), Err(Error::InvalidRecipient));
+            assert_eq!(
+                land.transfer(test::callee::<DefaultEnvironment>(), token_id),
+                Err(Error::InvalidRecipient)
+            );
+            assert_eq!(
+                land.mint_parcel(AccountId::from([0u8; 32]), 1, 9, 9),
+                Err(Error::InvalidRecipient)
+            );
+            assert_eq!(land.transfer(accounts.bob, token_id), Ok(()));
+            assert_eq!(land.owner_of(token_id), Some(accounts.bob));
+            assert_eq!(land.tokens_of_owner(accounts.alice), Vec::<u32>::new());
+            assert!(!land.balances.contains(accounts.alice));
+            assert_eq!(land.transfer(accounts.charlie, token_id), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn merge_and_split_round_trip() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let parcels = land.mint_region(accounts.alice, 1, 0, 0, 1, 1).unwrap();
+
+            let estate_id = land.merge(parcels.clone()).unwrap();
+            assert!(land.is_estate(estate_id));
+            assert!(land.exists(estate_id));
+            assert!(!land.exists(parcels[0]));
+            assert_eq!(land.estate_parcels(estate_id), parcels);
+            assert_eq!(land.tokens_of_owner(accounts.alice), vec![estate_id]);
+            assert_eq!(land.owner_of(parcels[0]), None);
+            assert_eq!(land.parcel_at(1, 1, 1), Some(estate_id));
+
+            // Estates move as a single token
+            assert_eq!(land.transfer(accounts.bob, estate_id), Ok(()));
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(land.split(estate_id), Ok(parcels.clone()));
+            assert!(!land.is_estate(estate_id));
+            assert_eq!(land.owner_of(estate_id), None);
+            assert_eq!(land.balance_of(accounts.bob), 4);
+            assert_eq!(land.parcel_at(1, 1, 1), Some(parcels[3]));
+            assert_eq!(land.coordinates_of(parcels[3]), Some(Coordinates { world_id: 1, x: 1, y: 1 }));
+        }
+
+        #[ink::test]
+        fn merge_rejects_invalid_sets() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let a = land.mint_parcel(accounts.alice, 1, 0, 0).unwrap();
+            let b = land.mint_parcel(accounts.alice, 1, 1, 0).unwrap();
+            let far = land.mint_parcel(accounts.alice, 1, 5, 5).unwrap();
+            let other_world = land.mint_parcel(accounts.alice, 2, 2, 0).unwrap();
+            let bobs = land.mint_parcel(accounts.bob, 1, 0, 1).unwrap();
+
+            assert_eq!(land.merge(vec![a]), Err(Error::InvalidMerge));
+            assert_eq!(land.merge(vec![a, a]), Err(Error::InvalidMerge));
+            assert_eq!(land.merge(vec![a, b, far]), Err(Error::NotAdjacent));
+            assert_eq!(land.merge(vec![a, b, other_world]), Err(Error::NotAdjacent));
+            assert_eq!(land.merge(vec![a, bobs]), Err(Error::NotOwner));
+            assert_eq!(land.split(a), Err(Error::NotEstate));
+
+            let estate_id = land.merge(vec![a, b]).unwrap();
+            assert_eq!(land.merge(vec![estate_id, far]), Err(Error::IsEstate));
+        }
+
+        fn transform() -> Transform {
+            Transform {
+                position: [1_500, 0, -250],
+                rotation: [0, 90_000, 0],
+                scale: 1_000,
+            }
+        }
+
+        #[ink::test]
+        fn place_and_remove_scene_items() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let parcel = land.mint_parcel(accounts.alice, 1, 0, 0).unwrap();
+            let other = land.mint_parcel(accounts.alice, 1, 1, 0).unwrap();
+            let props = accounts.django;
+
+            assert_eq!(land.place_in_scene(parcel, props, 7, transform()), Ok(()));
+            assert_eq!(
+                land.scene_contents(parcel),
+                vec![SceneItem {
+                    item: ItemRef { collection: props, token_id: 7 },
+                    transform: transform(),
+                }]
+            );
+            assert_eq!(land.placement_of(ItemRef { collection: props, token_id: 7 }), Some(parcel));
+
+            // An item can only be anchored once
+            assert_eq!(land.place_in_scene(other, props, 7, transform()), Err(Error::ItemAlreadyPlaced));
+            assert_eq!(land.remove_from_scene(other, props, 7), Err(Error::ItemNotInScene));
+
+            // Occupied parcels can't be merged away
+            assert_eq!(land.merge(vec![parcel, other]), Err(Error::SceneNotEmpty));
+
+            assert_eq!(land.remove_from_scene(parcel, props, 7), Ok(()));
+            assert!(land.scene_contents(parcel).is_empty());
+            assert_eq!(land.placement_of(ItemRef { collection: props, token_id: 7 }), None);
+        }
+
+        #[ink::test]
+        fn transferred_items_leave_the_scene() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let parcel = land.mint_parcel(accounts.alice, 1, 0, 0).unwrap();
+            let props = accounts.eve;
+            assert_eq!(land.place_in_scene(parcel, props, 7, transform()), Ok(()));
+
+            // Only the item's own collection can report its transfers
+            test::set_caller::<DefaultEnvironment>(accounts.frank);
+            land.after_transfer(Some(accounts.alice), Some(accounts.bob), 7);
+            assert_eq!(land.placement_of(ItemRef { collection: props, token_id: 7 }), Some(parcel));
+
+            test::set_caller::<DefaultEnvironment>(props);
+            assert!(land.before_transfer(Some(accounts.alice), Some(accounts.bob), 7));
+            land.after_transfer(Some(accounts.alice), Some(accounts.bob), 7);
+            assert_eq!(land.placement_of(ItemRef { collection: props, token_id: 7 }), None);
+            assert!(land.scene_contents(parcel).is_empty());
+        }
+
+        #[ink::test]
+        fn only_parcel_owner_edits_scene() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let parcel = land.mint_parcel(accounts.alice, 1, 0, 0).unwrap();
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(land.place_in_scene(parcel, accounts.django, 1, transform()), Err(Error::NotOwner));
+            assert_eq!(land.place_in_scene(99, accounts.django, 1, transform()), Err(Error::TokenNotFound));
+        }
+
+        #[ink::test]
+        fn builders_can_edit_scene() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let parcel = land.mint_parcel(accounts.alice, 1, 0, 0).unwrap();
+
+            assert_eq!(land.add_builder(parcel, accounts.bob), Ok(()));
+            assert_eq!(land.add_builder(parcel, accounts.bob), Err(Error::BuilderAlreadyAdded));
+            assert!(land.is_builder(parcel, accounts.bob));
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(land.place_in_scene(parcel, accounts.django, 1, transform()), Ok(()));
+            assert_eq!(land.remove_from_scene(parcel, accounts.django, 1), Ok(()));
+            // Builders can't manage the builder list
+            assert_eq!(land.add_builder(parcel, accounts.charlie), Err(Error::NotOwner));
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(land.remove_builder(parcel, accounts.bob), Ok(()));
+            assert_eq!(land.remove_builder(parcel, accounts.bob), Err(Error::BuilderNotFound));
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(land.place_in_scene(parcel, accounts.django, 1, transform()), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn builders_are_cleared_on_transfer() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let parcel = land.mint_parcel(accounts.alice, 1, 0, 0).unwrap();
+
+            land.add_builder(parcel, accounts.bob).unwrap();
+            land.transfer(accounts.charlie, parcel).unwrap();
+            assert!(land.builders_of(parcel).is_empty());
+        }
+
+        #[ink::test]
+        fn gates_control_entry() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let parcel = land.mint_parcel(accounts.alice, 1, 0, 0).unwrap();
+            let land_address = test::callee::<DefaultEnvironment>();
+
+            // Ungated parcels are open
+            assert!(land.can_enter(accounts.eve, parcel));
+            assert!(!land.can_enter(accounts.eve, 99));
+
+            // Require two parcels of this collection
+            assert_eq!(land.set_gate(parcel, land_address, 2, true), Ok(()));
+            land.mint_parcel(accounts.bob, 1, 5, 5).unwrap();
+            assert!(!land.can_enter(accounts.bob, parcel));
+            land.mint_parcel(accounts.bob, 1, 6, 6).unwrap();
+            assert!(land.can_enter(accounts.bob, parcel));
+
+            // Owner and builders always get in
+            assert!(land.can_enter(accounts.alice, parcel));
+            land.add_builder(parcel, accounts.charlie).unwrap();
+            assert!(land.can_enter(accounts.charlie, parcel));
+
+            // Removing the only rule reopens the parcel
+            assert_eq!(land.set_gate(parcel, land_address, 0, false), Ok(()));
+            assert!(land.gates_of(parcel).is_empty());
+            assert!(land.can_enter(accounts.eve, parcel));
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(land.set_gate(parcel, land_address, 1, false), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn portals_work() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let parcel = land.mint_parcel(accounts.alice, 1, 0, 0).unwrap();
+
+            assert_eq!(land.create_portal(parcel, 2, 40), Ok(()));
+            assert_eq!(land.create_portal(parcel, 2, 40), Err(Error::PortalExists));
+            assert_eq!(land.portals_of(parcel), vec![Portal { to_world: 2, to_parcel: 40 }]);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(land.create_portal(parcel, 3, 1), Err(Error::NotOwner));
+            assert_eq!(land.remove_portal(parcel, 2, 40), Err(Error::NotOwner));
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(land.remove_portal(parcel, 3, 1), Err(Error::PortalNotFound));
+            assert_eq!(land.remove_portal(parcel, 2, 40), Ok(()));
+            assert!(land.portals_of(parcel).is_empty());
+        }
+
+        #[ink::test]
+        fn snapshots_build_history() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let parcel = land.mint_parcel(accounts.alice, 1, 0, 0).unwrap();
+            land.add_builder(parcel, accounts.bob).unwrap();
+
+            assert_eq!(land.latest_snapshot(parcel), None);
+            assert_eq!(land.commit_scene_snapshot(parcel, Hash::from([1; 32])), Ok(0));
+
+            test::advance_block::<DefaultEnvironment>();
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(land.commit_scene_snapshot(parcel, Hash::from([2; 32])), Ok(1));
+
+            let history = land.scene_snapshots(parcel, 0, 10);
+            assert_eq!(history.len(), 2);
+            assert_eq!(history[0].committer, accounts.alice);
+            assert_eq!(history[1].block, history[0].block + 1);
+            assert_eq!(land.scene_snapshots(parcel, 1, 10), vec![history[1]]);
+            assert_eq!(land.latest_snapshot(parcel), Some(history[1]));
+
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(land.commit_scene_snapshot(parcel, Hash::from([3; 32])), Err(Error::NotOwner));
+            assert_eq!(land.snapshot_count(parcel), 2);
+        }
+
+        #[ink::test]
+        fn neighbors_and_region_queries() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let row = land.mint_region(accounts.alice, 1, 0, 0, 2, 0).unwrap();
+            let north = land.mint_parcel(accounts.bob, 1, 1, 1).unwrap();
+
+            // Middle parcel: north, east, south, west
+            assert_eq!(land.neighbors_of(row[1]), vec![Some(north), Some(row[2]), None, Some(row[0])]);
+            assert!(land.neighbors_of(99).is_empty());
+
+            // Merged neighbours resolve to the estate
+            let estate = land.merge(vec![row[1], row[2]]).unwrap();
+            assert_eq!(land.neighbors_of(row[0])[1], Some(estate));
+            assert!(land.neighbors_of(estate).is_empty());
+
+            // 3x2 region, paged two cells at a time
+            let page = |start| land.parcels_in_region(1, 0, 0, 2, 1, start, 2);
+            assert_eq!(
+                page(0),
+                vec![
+                    (Coordinates { world_id: 1, x: 0, y: 0 }, row[0]),
+                    (Coordinates { world_id: 1, x: 1, y: 0 }, estate),
+                ]
+            );
+            assert_eq!(page(2), vec![(Coordinates { world_id: 1, x: 2, y: 0 }, estate)]);
+            assert_eq!(page(4), vec![(Coordinates { world_id: 1, x: 1, y: 1 }, north)]);
+            assert!(page(6).is_empty());
+            assert!(land.parcels_in_region(1, 2, 0, 0, 0, 0, 10).is_empty());
+        }
+
+        #[ink::test]
+        fn districts_are_configured_by_their_treasury() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let row = land.mint_region(accounts.bob, 1, 0, 0, 2, 0).unwrap();
+            let outside = land.mint_parcel(accounts.bob, 1, 5, 5).unwrap();
+
+            let district = land.create_district(1, 0, 0, 3, 3, accounts.eve, 1_000).unwrap();
+            assert_eq!(land.district_of(row[0]), Some(district));
+            assert_eq!(land.district_of(outside), None);
+            assert_eq!(
+                land.create_district(1, 3, 3, 4, 4, accounts.eve, 0),
+                Err(Error::DistrictOverlap)
+            );
+            assert_eq!(
+                land.create_district(2, 0, 0, 1, 1, accounts.eve, MAX_DISTRICT_SHARE_BPS + 1),
+                Err(Error::InvalidShare)
+            );
+
+            // Estates belong to the district of their first parcel
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let estate = land.merge(row).unwrap();
+            assert_eq!(land.district_of(estate), Some(district));
+            assert_eq!(land.create_district(2, 0, 0, 1, 1, accounts.bob, 0), Err(Error::NotAdmin));
+            assert_eq!(
+                land.configure_district(district, accounts.bob, 0),
+                Err(Error::NotTreasury)
+            );
+
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            land.configure_district(district, accounts.frank, 2_500).unwrap();
+            assert_eq!(land.district(district).map(|d| (d.treasury, d.share_bps)), Some((accounts.frank, 2_500)));
+        }
+
+        #[ink::test]
+        fn locked_parcels_stay_put() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let row = land.mint_region(accounts.alice, 1, 0, 0, 1, 0).unwrap();
+            assert_eq!(
+                land.sacrifice(accounts.eve, 0, row[0], true, MIN_SACRIFICE_LOCK - 1),
+                Err(Error::LockTooShort)
+            );
+
+            // The off-chain environment can't call the governance contract,
+            // so lock the parcel as an accepted sacrifice would
+            land.sacrifice_locks.insert(row[0], &2);
+            assert_eq!(land.transfer(accounts.bob, row[0]), Err(Error::Locked));
+            assert_eq!(land.merge(row.clone()), Err(Error::Locked));
+
+            for _ in 0..3 {
+                test::advance_block::<DefaultEnvironment>();
+            }
+            assert_eq!(land.locked_until(row[0]), None);
+            assert!(land.merge(row).is_ok());
+        }
+
+        #[ink::test]
+        fn district_owners_elect_a_council() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let bob_row = land.mint_region(accounts.bob, 1, 0, 0, 2, 0).unwrap();
+            let charlie_parcel = land.mint_parcel(accounts.charlie, 1, 0, 1).unwrap();
+            let outside = land.mint_parcel(accounts.charlie, 1, 9, 9).unwrap();
+            let district = land.create_district(1, 0, 0, 3, 3, accounts.eve, 0).unwrap();
+            let config = CouncilConfig {
+                seats: 1,
+                nomination_blocks: 2,
+                voting_blocks: 2,
+                term_blocks: 10,
+            };
+            assert_eq!(land.start_council_election(district), Err(Error::CouncilNotConfigured));
+            assert_eq!(land.configure_council(district, config), Err(Error::NotTreasury));
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            land.configure_council(district, config).unwrap();
+            assert_eq!(land.start_council_election(district), Ok(1));
+
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(land.nominate(district, outside), Err(Error::NotInDistrict));
+            land.nominate(district, charlie_parcel).unwrap();
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let estate = land.merge(bob_row).unwrap();
+            land.nominate(district, estate).unwrap();
+            assert_eq!(
+                land.vote_council(district, estate, vec![accounts.bob]),
+                Err(Error::WrongElectionPhase)
+            );
+
+            for _ in 0..3 {
+                test::advance_block::<DefaultEnvironment>();
+            }
+            assert_eq!(
+                land.vote_council(district, estate, vec![accounts.bob, accounts.charlie]),
+                Err(Error::InvalidBallot)
+            );
+            assert_eq!(land.vote_council(district, estate, vec![accounts.bob]), Ok(3));
+            // Splitting the estate doesn't give its parcels a second vote
+            let parcels = land.split(estate).unwrap();
+            assert_eq!(
+                land.vote_council(district, parcels[0], vec![accounts.bob]),
+                Err(Error::AlreadyVoted)
+            );
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(land.vote_council(district, charlie_parcel, vec![accounts.charlie]), Ok(1));
+            assert_eq!(land.close_council_election(district), Err(Error::WrongElectionPhase));
+
+            for _ in 0..2 {
+                test::advance_block::<DefaultEnvironment>();
+            }
+            assert_eq!(land.close_council_election(district), Ok(vec![accounts.bob]));
+            assert!(land.is_council_member(district, accounts.bob));
+            assert_eq!(
+                land.set_district_metadata(district, String::from("ipfs://district")),
+                Err(Error::NotCouncilMember)
+            );
+            assert_eq!(land.start_council_election(district), Err(Error::CouncilInOffice));
+
+            // The council's entry rules apply on top of each parcel's own
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            land.set_district_metadata(district, String::from("ipfs://district")).unwrap();
+            assert_eq!(land.district_metadata(district), Some(String::from("ipfs://district")));
+            assert!(land.can_enter(accounts.django, charlie_parcel));
+            let gate = GateRule {
+                collection: test::callee::<DefaultEnvironment>(),
+                min_balance: 1,
+                count_linked: false,
+            };
+            land.set_district_gates(district, vec![gate]).unwrap();
+            assert!(!land.can_enter(accounts.django, charlie_parcel));
+            assert!(land.can_enter(accounts.bob, charlie_parcel));
+            assert!(land.can_enter(accounts.django, outside));
+
+            // Once the term ends the council loses its rights
+            for _ in 0..10 {
+                test::advance_block::<DefaultEnvironment>();
+            }
+            assert_eq!(land.council(district), None);
+            assert_eq!(land.set_district_gates(district, Vec::new()), Err(Error::NotCouncilMember));
+            assert_eq!(land.start_council_election(district), Ok(2));
+        }
+
+        #[cfg(feature = "rental")]
+        #[ink::test]
+        fn rent_shares_income_with_the_district() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            let parcel = land.mint_parcel(accounts.bob, 1, 0, 0).unwrap();
+            let neighbor = land.mint_parcel(accounts.bob, 1, 1, 0).unwrap();
+            let district = land.create_district(1, 0, 0, 9, 9, accounts.eve, 2_000).unwrap();
+            test::set_account_balance::<DefaultEnvironment>(test::callee::<DefaultEnvironment>(), 10_000);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            land.offer_rental(parcel, 10, 5).unwrap();
+
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            test::set_value_transferred::<DefaultEnvironment>(60);
+            assert_eq!(land.rent(parcel, 6), Err(Error::InvalidRentalTerm));
+            test::set_value_transferred::<DefaultEnvironment>(40);
+            assert_eq!(land.rent(parcel, 5), Err(Error::WrongPayment));
+
+            let bob_before = test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap();
+            test::set_value_transferred::<DefaultEnvironment>(50);
+            let until = land.rent(parcel, 5).unwrap();
+            assert_eq!(land.rental_of(parcel), Some(Rental { renter: accounts.charlie, until }));
+            assert_eq!(land.district_revenue(district), 10);
+            assert_eq!(
+                test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap(),
+                bob_before + 40
+            );
+
+            // The renter builds on the parcel, and the owner can't restructure it
+            land.place_in_scene(parcel, accounts.django, 1, transform()).unwrap();
+            test::set_caller::<DefaultEnvironment>(accounts.django);
+            assert_eq!(land.rent(parcel, 1), Err(Error::Rented));
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            land.remove_from_scene(parcel, accounts.django, 1).unwrap();
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(land.merge(vec![parcel, neighbor]), Err(Error::Rented));
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(land.withdraw_district_revenue(district), Err(Error::NotTreasury));
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            let eve_before = test::get_account_balance::<DefaultEnvironment>(accounts.eve).unwrap();
+            assert_eq!(land.withdraw_district_revenue(district), Ok(10));
+            assert_eq!(land.district_revenue(district), 0);
+            assert_eq!(
+                test::get_account_balance::<DefaultEnvironment>(accounts.eve).unwrap(),
+                eve_before + 10
+            );
+
+            // Expired rentals free the parcel again
+            for _ in 0..5 {
+                test::advance_block::<DefaultEnvironment>();
+            }
+            assert_eq!(land.rental_of(parcel), None);
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(land.merge(vec![parcel, neighbor]).is_ok());
+        }
+
+        #[ink::test]
+        fn world_name_deposits_come_back_on_publishing() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+            land.set_name_deposit(100).unwrap();
+            land.set_curator(accounts.frank, true).unwrap();
+            let parcel = land.mint_parcel(accounts.bob, 1, 0, 0).unwrap();
+            test::set_account_balance::<DefaultEnvironment>(test::callee::<DefaultEnvironment>(), 1_000);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            test::set_value_transferred::<DefaultEnvironment>(50);
+            assert_eq!(land.reserve_world_name(1, String::from("Genesis")), Err(Error::WrongPayment));
+            test::set_value_transferred::<DefaultEnvironment>(100);
+            assert_eq!(land.reserve_world_name(1, String::new()), Err(Error::InvalidName));
+            land.reserve_world_name(1, String::from("Genesis")).unwrap();
+            assert_eq!(land.world_by_name(String::from("Genesis")), Some(1));
+
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(land.reserve_world_name(2, String::from("Genesis")), Err(Error::NameTaken));
+            land.reserve_world_name(2, String::from("Squat")).unwrap();
+
+            // Bob's snapshot in world 1 returns his deposit
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let bob_before = test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap();
+            land.commit_scene_snapshot(parcel, Hash::from([1; 32])).unwrap();
+            assert!(land.world_name(1).unwrap().published);
+            assert_eq!(
+                test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap(),
+                bob_before + 100
+            );
+            assert_eq!(land.verify_world(2), Err(Error::NotCurator));
+
+            test::set_caller::<DefaultEnvironment>(accounts.frank);
+            assert_eq!(land.verify_world(1), Err(Error::AlreadyPublished));
+
+            // The admin takes back the squatted name and keeps the deposit
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(land.reclaim_world_name(1), Err(Error::AlreadyPublished));
+            land.reclaim_world_name(2).unwrap();
+            assert_eq!(land.world_name(2), None);
+            assert_eq!(land.world_by_name(String::from("Squat")), None);
+        }
+
+        #[ink::test]
+        fn counters_fail_at_their_limits() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut land = new_land();
+
+            // Far-apart coordinates must not wrap around into adjacency
+            let west = Coordinates { world_id: 1, x: i32::MIN, y: 0 };
+            let east = Coordinates { world_id: 1, x: i32::MAX, y: 2 };
+            assert!(!west.is_adjacent(&east));
+
+            land.next_token_id = u32::MAX;
+            assert_eq!(land.mint_parcel(accounts.alice, 1, 0, 0), Err(Error::Overflow));
+            assert_eq!(land.balance_of(accounts.alice), 0);
+            assert_eq!(land.parcel_at(1, 0, 0), None);
+
+            land.next_token_id = 1;
+            let parcel = land.mint_parcel(accounts.alice, 1, 0, 0).unwrap();
+            land.snapshot_count.insert(parcel, &u32::MAX);
+            assert_eq!(
+                land.commit_scene_snapshot(parcel, Hash::from([1; 32])),
+                Err(Error::Overflow)
+            );
+        }
+    }
+
+    /// Gas benchmarks against a live node.
+    ///
+    /// Build the contract with `cargo contract build --manifest-path
+    /// contracts/vr_land/Cargo.toml`, then run `cargo test -p vr_land --features
+    /// e2e-tests gas_report -- --nocapture`. Scene edits are dry-run against
+    /// scenes of several sizes, since a parcel's items are stored together,
+    /// and the results are written like the collection's gas report to
+    /// `$GAS_REPORT` (default `target/gas-report-vr_land.json`). The run fails
+    /// if any edit got more than `$GAS_TOLERANCE` percent (default 5) costlier
+    /// than in `golden/gas/vr_land.json`; rerun with `UPDATE_GOLDEN=1` to
+    /// accept new numbers.
+    ///
+    /// The node is `$CONTRACTS_NODE` (default `substrate-contracts-node`) and
+    /// the contract `$E2E_CONTRACT_WASM` (default `target/ink/vr_land/vr_land.wasm`).
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_benches {
+        use super::*;
+        use ink_e2e::{build_message, PolkadotConfig};
+
+        type Client = ink_e2e::Client<PolkadotConfig, ink::env::DefaultEnvironment>;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        /// Items already in the scene when each edit is measured
+        const SCENE_SIZES: [u32; 3] = [0, 10, 100];
+
+        include!("../conformance/gas_guard.rs");
+
+        fn transform() -> Transform {
+            Transform {
+                position: [1_500, 0, -250],
+                rotation: [0, 90_000, 0],
+                scale: 1_000,
+            }
+        }
+
+        #[test]
+        fn gas_report() -> E2EResult<()> {
+            let node = std::env::var("CONTRACTS_NODE")
+                .unwrap_or_else(|_| String::from("substrate-contracts-node"));
+            let wasm = std::env::var("E2E_CONTRACT_WASM").unwrap_or_else(|_| {
+                concat!(env!("CARGO_MANIFEST_DIR"), "/../../target/ink/vr_land/vr_land.wasm").into()
+            });
+            ink_e2e::tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?
+                .block_on(async {
+                    let node = ink_e2e::TestNodeProcess::<PolkadotConfig>::build(node).spawn().await?;
+                    let mut client = Client::new(node.client(), [wasm.as_str()]).await;
+                    let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+                    let props = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
+                    let contract = client
+                        .instantiate("vr_land", &ink_e2e::alice(), VrLandRef::new(), 0, None)
+                        .await
+                        .expect("instantiate failed")
+                        .account_id;
+                    let mut samples = Vec::new();
+
+                    for (x, scene_size) in SCENE_SIZES.into_iter().enumerate() {
+                        let mint = build_message::<VrLandRef>(contract)
+                            .call(|land| land.mint_parcel(alice, 1, x as i32, 0));
+                        let parcel = client
+                            .call(&ink_e2e::alice(), mint, 0, None)
+                            .await
+                            .expect("mint_parcel failed")
+                            .return_value()
+                            .expect("mint_parcel rejected");
+                        for item in 0..scene_size {
+                            let place = build_message::<VrLandRef>(contract)
+                                .call(|land| land.place_in_scene(parcel, props, parcel * 1_000 + item, transform()));
+                            client
+                                .call(&ink_e2e::alice(), place, 0, None)
+                                .await
+                                .expect("place_in_scene failed")
+                                .return_value()
+                                .expect("place_in_scene rejected");
+                        }
+
+                        let place = build_message::<VrLandRef>(contract)
+                            .call(|land| land.place_in_scene(parcel, props, parcel * 1_000 + scene_size, transform()));
+                        let place = client.call_dry_run(&ink_e2e::alice(), &place, 0, None).await;
+
+                        let mut dry_runs = vec![("place_in_scene", place.exec_result)];
+                        if scene_size > 0 {
+                            // The last item placed, found at the end of the scene
+                            let remove = build_message::<VrLandRef>(contract)
+                                .call(|land| land.remove_from_scene(parcel, props, parcel * 1_000 + scene_size - 1));
+                            let remove = client.call_dry_run(&ink_e2e::alice(), &remove, 0, None).await;
+                            dry_runs.push(("remove_from_scene", remove.exec_result));
+                        }
+
+                        for (message, dry_run) in dry_runs {
+                            assert!(dry_run.result.is_ok(), "{} failed", message);
+                            samples.push(Sample {
+                                message,
+                                size: scene_size,
+                                ref_time: dry_run.gas_consumed.ref_time(),
+                                proof_size: dry_run.gas_consumed.proof_size(),
+                            });
+                        }
+                    }
+
+                    write_report(&samples, concat!(env!("CARGO_MANIFEST_DIR"), "/../../target/gas-report-vr_land.json"))?;
+                    check_gas_baseline(&samples, concat!(env!("CARGO_MANIFEST_DIR"), "/../../golden/gas/vr_land.json"));
+                    Ok(())
+                })
+        }
+    }
+}
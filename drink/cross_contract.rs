@@ -0,0 +1,352 @@
+//! Cross-contract scenarios run in a drink! sandbox: a runtime with
+//! `pallet_contracts` in process, so several contracts can call each other
+//! without a node. The off-chain unit tests can't make cross-contract calls
+//! and the ink_e2e suite needs a node, so these sit in between.
+//!
+//! drink! builds contracts without features, like ink_e2e, so these load
+//! bundles built beforehand from the repository root:
+//!
+//! ```text
+//! cargo contract build --features marketplace
+//! cargo contract build --manifest-path contracts/collection_factory/Cargo.toml
+//! cargo contract build --manifest-path contracts/nft_staking/Cargo.toml
+//! for double in reentrant_receiver gas_griefing_hook reverting_buyer; do
+//!     cargo contract build --manifest-path contracts/test_doubles/$double/Cargo.toml
+//! done
+//! cd drink && cargo test
+//! ```
+//!
+//! Bundles are looked up in `$DRINK_BUNDLES` (default `target/ink`), either
+//! directly or in a directory named after the contract.
+
+use std::error::Error as StdError;
+use std::path::PathBuf;
+
+use asset_hub_nft_final::asset_hub_nft::Error;
+use drink::runtime::MinimalRuntime;
+use drink::session::{ContractBundle, Session, NO_ARGS, NO_ENDOWMENT};
+use drink::AccountId32;
+use reentrant_receiver::reentrant_receiver::Reentry;
+use scale::{Decode, Encode};
+
+type TestResult = Result<(), Box<dyn StdError>>;
+
+/// Funds given to each account a test acts as
+const INITIAL_BALANCE: u128 = 1_000_000_000_000;
+
+/// Gas each transfer hook call may use
+const HOOK_GAS_LIMIT: u64 = 50_000_000_000;
+
+/// Load a contract's `.contract` bundle
+fn bundle(name: &str) -> Result<ContractBundle, Box<dyn StdError>> {
+    let dir = std::env::var("DRINK_BUNDLES")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/../target/ink")));
+    let file = format!("{name}.contract");
+    let path = [dir.join(&file), dir.join(name).join(&file)]
+        .into_iter()
+        .find(|path| path.exists())
+        .ok_or_else(|| format!("no bundle for {name} in {}; build it first", dir.display()))?;
+    Ok(ContractBundle::load(path)?)
+}
+
+/// A funded account, distinct per seed
+fn account(session: &mut Session<MinimalRuntime>, seed: u8) -> AccountId32 {
+    let account = AccountId32::new([seed; 32]);
+    session
+        .sandbox()
+        .mint_into(account.clone(), INITIAL_BALANCE)
+        .expect("funding failed");
+    account
+}
+
+/// Call a message as the current actor and decode what it returned.
+/// Messages that revert fail the test.
+fn call<T: Decode>(
+    session: &mut Session<MinimalRuntime>,
+    contract: &AccountId32,
+    message: &str,
+    args: &[String],
+    value: Option<u128>,
+) -> T {
+    session
+        .call_with_address(contract.clone(), message, args, value)
+        .unwrap_or_else(|err| panic!("{message} failed: {err:?}"))
+        .unwrap_or_else(|err| panic!("{message} hit a language error: {err:?}"))
+}
+
+/// Deploy a collection with the default config, administered by the current actor
+fn deploy_collection(session: &mut Session<MinimalRuntime>) -> Result<AccountId32, Box<dyn StdError>> {
+    Ok(session.deploy_bundle(bundle("asset_hub_nft_final")?, "new_default", NO_ARGS, vec![1], NO_ENDOWMENT)?)
+}
+
+/// Deploy a test double from `contracts/test_doubles` and register it as a
+/// transfer hook on `collection`, as the collection's admin
+fn deploy_hook(
+    session: &mut Session<MinimalRuntime>,
+    collection: &AccountId32,
+    name: &str,
+    args: &[String],
+) -> Result<AccountId32, Box<dyn StdError>> {
+    let hook = session.deploy_bundle(bundle(name)?, "new", args, vec![5], NO_ENDOWMENT)?;
+    let args = [hook.to_string(), HOOK_GAS_LIMIT.to_string()];
+    assert!(call::<bool>(session, collection, "add_transfer_hook", &args, None));
+    Ok(hook)
+}
+
+/// Mint a token to `to` as the current actor, who must be the admin
+fn mint_to(session: &mut Session<MinimalRuntime>, collection: &AccountId32, to: &AccountId32) -> u32 {
+    let minted: Result<u32, Error> =
+        call(session, collection, "mint", &[to.to_string(), "\"ipfs://QmDouble\"".into()], None);
+    minted.expect("mint rejected")
+}
+
+/// Get a token's owner
+fn owner_of(session: &mut Session<MinimalRuntime>, collection: &AccountId32, token_id: u32) -> Option<AccountId32> {
+    call(session, collection, "PSP34Core::owner_of", &[token_id.to_string()], None)
+}
+
+#[test]
+fn marketplace_settlement_pays_fees_and_reports_royalties() -> TestResult {
+    let mut session = Session::<MinimalRuntime>::new()?;
+    let admin = account(&mut session, 1);
+    let seller = account(&mut session, 2);
+    let buyer = account(&mut session, 3);
+    let treasury = account(&mut session, 4);
+    session.set_actor(admin.clone());
+    let collection = deploy_collection(&mut session)?;
+
+    assert!(call::<bool>(&mut session, &collection, "set_royalty_bps", &["500".into()], None));
+    assert!(call::<bool>(&mut session, &collection, "set_protocol_fee", &["250".into()], None));
+    assert!(call::<bool>(&mut session, &collection, "set_treasury", &[treasury.to_string()], None));
+    let minted: Result<u32, Error> = call(
+        &mut session,
+        &collection,
+        "mint",
+        &[seller.to_string(), "\"ipfs://QmDrink\"".into()],
+        None,
+    );
+    let token_id = minted.expect("mint rejected");
+
+    // The creator stays the admin who minted; a resale is priced off their royalty
+    session.set_actor(seller.clone());
+    assert!(call::<bool>(&mut session, &collection, "list", &[token_id.to_string(), "10000".into()], None));
+    session.set_actor(buyer.clone());
+    let bought: Result<(), Error> =
+        call(&mut session, &collection, "buy", &[token_id.to_string()], Some(10_000));
+    assert_eq!(bought, Ok(()));
+
+    let owner: Option<AccountId32> =
+        call(&mut session, &collection, "PSP34Core::owner_of", &[token_id.to_string()], None);
+    assert_eq!(owner, Some(buyer));
+    let proceeds: u128 =
+        call(&mut session, &collection, "withdrawable", &[seller.to_string(), "Native".into()], None);
+    assert_eq!(proceeds, 9_750);
+    let fee: u128 =
+        call(&mut session, &collection, "withdrawable", &[treasury.to_string(), "Native".into()], None);
+    assert_eq!(fee, 250);
+    let royalty_args = [token_id.to_string(), "10000".into()];
+    let royalty: Option<(AccountId32, u128)> =
+        call(&mut session, &collection, "Royalty::royalty_info", &royalty_args, None);
+    assert_eq!(royalty, Some((admin, 500)));
+    Ok(())
+}
+
+#[test]
+fn factory_deploys_collections_for_their_creators() -> TestResult {
+    let mut session = Session::<MinimalRuntime>::new()?;
+    let deployer = account(&mut session, 1);
+    let creator = account(&mut session, 2);
+    session.set_actor(deployer);
+    let code_hash = session.upload_bundle(bundle("asset_hub_nft_final")?)?;
+    let factory = session.deploy_bundle(
+        bundle("collection_factory")?,
+        "new",
+        &[format!("{code_hash:?}")],
+        vec![2],
+        NO_ENDOWMENT,
+    )?;
+
+    session.set_actor(creator.clone());
+    let config = "CollectionConfig { name: \"Drink Frames\", symbol: \"DRK\", max_supply: Some(2), \
+                  base_uri: \"ipfs://\", royalty_bps: 0, open_mint: false, soulbound: false, \
+                  mint_deposit: 0, history_depth: 8 }";
+    // The factory's errors are plain variants, so their index is enough here
+    let created: Result<AccountId32, u8> =
+        call(&mut session, &factory, "create_collection", &[config.into()], None);
+    let collection = created.expect("factory rejected the collection");
+    let count: u32 = call(&mut session, &factory, "collection_count", NO_ARGS, None);
+    assert_eq!(count, 1);
+
+    // The creator, not the factory, administers what it deployed
+    let admin: AccountId32 = call(&mut session, &collection, "admin", NO_ARGS, None);
+    assert_eq!(admin, creator);
+    let name: String = call(&mut session, &collection, "Metadata::name", NO_ARGS, None);
+    assert_eq!(name, "Drink Frames");
+    let minted: Result<u32, Error> =
+        call(&mut session, &collection, "mint", &[creator.to_string(), "\"ipfs://QmFactory\"".into()], None);
+    assert_eq!(minted, Ok(1));
+    Ok(())
+}
+
+#[test]
+fn transfer_hooks_lock_staked_tokens() -> TestResult {
+    let mut session = Session::<MinimalRuntime>::new()?;
+    let admin = account(&mut session, 1);
+    let holder = account(&mut session, 2);
+    let recipient = account(&mut session, 3);
+    session.set_actor(admin);
+    let collection = deploy_collection(&mut session)?;
+    let staking = session.deploy_bundle(
+        bundle("nft_staking")?,
+        "new",
+        &[collection.to_string(), "10".into()],
+        vec![3],
+        NO_ENDOWMENT,
+    )?;
+    let hooked = call::<bool>(
+        &mut session,
+        &collection,
+        "add_transfer_hook",
+        &[staking.to_string(), HOOK_GAS_LIMIT.to_string()],
+        None,
+    );
+    assert!(hooked);
+    let minted: Result<u32, Error> =
+        call(&mut session, &collection, "mint", &[holder.to_string(), "\"ipfs://QmStaked\"".into()], None);
+    let token_id = minted.expect("mint rejected");
+
+    // Staking asks the collection who owns the token
+    session.set_actor(holder.clone());
+    let staked: Result<(), u8> = call(&mut session, &staking, "stake", &[token_id.to_string()], None);
+    assert_eq!(staked, Ok(()));
+
+    // The hook vetoes the move, so the collection reverts with `HookRejected`
+    let transfer_args = [recipient.to_string(), token_id.to_string()];
+    let locked = session.call_with_address(collection.clone(), "transfer", &transfer_args, NO_ENDOWMENT);
+    assert!(locked.is_err());
+    let owner: Option<AccountId32> =
+        call(&mut session, &collection, "PSP34Core::owner_of", &[token_id.to_string()], None);
+    assert_eq!(owner, Some(holder));
+
+    let unstaked: Result<u128, u8> = call(&mut session, &staking, "unstake", &[token_id.to_string()], None);
+    assert!(unstaked.is_ok());
+    let moved: Result<(), Error> = call(&mut session, &collection, "transfer", &transfer_args, None);
+    assert_eq!(moved, Ok(()));
+    Ok(())
+}
+
+#[test]
+fn reentrant_receivers_cannot_buy_a_token_twice() -> TestResult {
+    let mut session = Session::<MinimalRuntime>::new()?;
+    let admin = account(&mut session, 1);
+    let seller = account(&mut session, 2);
+    let buyer = account(&mut session, 3);
+    session.set_actor(admin);
+    let collection = deploy_collection(&mut session)?;
+    let receiver = deploy_hook(&mut session, &collection, "reentrant_receiver", &[collection.to_string()])?;
+    let token_id = mint_to(&mut session, &collection, &seller);
+
+    session.set_actor(seller.clone());
+    assert!(call::<bool>(&mut session, &collection, "list", &[token_id.to_string(), "10000".into()], None));
+    session.set_actor(buyer.clone());
+    let bought: Result<(), Error> =
+        call(&mut session, &collection, "buy", &[token_id.to_string()], Some(10_000));
+    assert_eq!(bought, Ok(()));
+
+    // The receiver tried to buy the token again mid-sale and got nowhere
+    let reentry: Reentry = call(&mut session, &receiver, "last_reentry", NO_ARGS, None);
+    assert!(matches!(reentry, Reentry::Denied | Reentry::Rejected(_)), "{reentry:?}");
+    assert_eq!(owner_of(&mut session, &collection, token_id), Some(buyer));
+    let proceeds: u128 =
+        call(&mut session, &collection, "withdrawable", &[seller.to_string(), "Native".into()], None);
+    assert_eq!(proceeds, 10_000);
+    Ok(())
+}
+
+#[test]
+fn gas_griefing_hooks_only_reject_the_transfer() -> TestResult {
+    let mut session = Session::<MinimalRuntime>::new()?;
+    let admin = account(&mut session, 1);
+    let holder = account(&mut session, 2);
+    let recipient = account(&mut session, 3);
+    session.set_actor(admin.clone());
+    let collection = deploy_collection(&mut session)?;
+    // Hooks also run on mints, so the griefer arrives after the token
+    let token_id = mint_to(&mut session, &collection, &holder);
+    let hook = deploy_hook(&mut session, &collection, "gas_griefing_hook", &["true".into(), "false".into()])?;
+
+    // The hook runs out of its own gas limit, and the transfer is rejected
+    // rather than the whole call running dry
+    session.set_actor(holder.clone());
+    let transfer_args = [recipient.to_string(), token_id.to_string()];
+    let griefed = session.call_with_address(collection.clone(), "transfer", &transfer_args, NO_ENDOWMENT);
+    assert!(griefed.is_err());
+    assert_eq!(owner_of(&mut session, &collection, token_id), Some(holder.clone()));
+
+    session.set_actor(admin);
+    assert!(call::<bool>(&mut session, &collection, "remove_transfer_hook", &[hook.to_string()], None));
+    session.set_actor(holder);
+    let moved: Result<(), Error> = call(&mut session, &collection, "transfer", &transfer_args, None);
+    assert_eq!(moved, Ok(()));
+    Ok(())
+}
+
+#[test]
+fn gas_griefing_after_hooks_cannot_undo_a_transfer() -> TestResult {
+    let mut session = Session::<MinimalRuntime>::new()?;
+    let admin = account(&mut session, 1);
+    let holder = account(&mut session, 2);
+    let recipient = account(&mut session, 3);
+    session.set_actor(admin);
+    let collection = deploy_collection(&mut session)?;
+    deploy_hook(&mut session, &collection, "gas_griefing_hook", &["false".into(), "true".into()])?;
+    let token_id = mint_to(&mut session, &collection, &holder);
+
+    session.set_actor(holder);
+    let transfer_args = [recipient.to_string(), token_id.to_string()];
+    let moved: Result<(), Error> = call(&mut session, &collection, "transfer", &transfer_args, None);
+    assert_eq!(moved, Ok(()));
+    assert_eq!(owner_of(&mut session, &collection, token_id), Some(recipient));
+    Ok(())
+}
+
+#[test]
+fn sales_to_buyers_that_revert_on_receipt_are_undone_whole() -> TestResult {
+    let mut session = Session::<MinimalRuntime>::new()?;
+    let admin = account(&mut session, 1);
+    let seller = account(&mut session, 2);
+    let bidder = account(&mut session, 3);
+    session.set_actor(admin);
+    let collection = deploy_collection(&mut session)?;
+    let buyer = deploy_hook(&mut session, &collection, "reverting_buyer", &[collection.to_string()])?;
+    let token_id = mint_to(&mut session, &collection, &seller);
+    session.set_actor(seller.clone());
+    assert!(call::<bool>(&mut session, &collection, "list", &[token_id.to_string(), "10000".into()], None));
+
+    // The buyer traps on delivery, so the purchase and its payment roll back
+    session.set_actor(bidder);
+    let refused: Option<u8> = call(&mut session, &buyer, "buy", &[token_id.to_string()], Some(10_000));
+    assert_eq!(refused, Some(Error::HookRejected.encode()[0]));
+    assert_eq!(owner_of(&mut session, &collection, token_id), Some(seller.clone()));
+    let price: Option<u128> = call(&mut session, &collection, "listing_price", &[token_id.to_string()], None);
+    assert_eq!(price, Some(10_000));
+    let proceeds: u128 =
+        call(&mut session, &collection, "withdrawable", &[seller.to_string(), "Native".into()], None);
+    assert_eq!(proceeds, 0);
+
+    // Accepting its offer fails the same way and leaves the escrow in place
+    assert!(call::<bool>(&mut session, &buyer, "make_offer", &[token_id.to_string()], Some(5_000)));
+    session.set_actor(seller.clone());
+    let accept_args = [token_id.to_string(), buyer.to_string()];
+    let accepted = session.call_with_address(collection.clone(), "accept_offer", &accept_args, NO_ENDOWMENT);
+    assert!(accepted.is_err());
+    assert_eq!(owner_of(&mut session, &collection, token_id), Some(seller));
+    let offer: Option<u128> = call(&mut session, &collection, "offer", &accept_args, None);
+    assert_eq!(offer, Some(5_000));
+
+    assert!(call::<bool>(&mut session, &buyer, "withdraw_offer", &[token_id.to_string()], None));
+    let offer: Option<u128> = call(&mut session, &collection, "offer", &accept_args, None);
+    assert_eq!(offer, None);
+    Ok(())
+}
@@ -0,0 +1,24 @@
+//! Feed arbitrary bytes into the contract's call decoders and argument types.
+//!
+//! Run with `cargo +nightly fuzz run decode_inputs` from the repository root.
+//! Decoding is allowed to fail, but it must never panic.
+#![no_main]
+
+use asset_hub_nft_final::asset_hub_nft::{AssetHubNFT, Listing, Query};
+use ink::reflect::{ContractConstructorDecoder, ContractMessageDecoder, DecodeDispatch};
+use libfuzzer_sys::fuzz_target;
+use scale::Decode;
+
+type Messages = <AssetHubNFT as ContractMessageDecoder>::Type;
+type Constructors = <AssetHubNFT as ContractConstructorDecoder>::Type;
+
+fuzz_target!(|data: &[u8]| {
+    // Selector followed by SCALE-encoded arguments, as the runtime delivers them
+    let _ = Messages::decode_dispatch(&mut &data[..]);
+    let _ = Constructors::decode_dispatch(&mut &data[..]);
+
+    // Argument types on their own, so failures point at the type
+    let _ = Vec::<Query>::decode(&mut &data[..]);
+    let _ = Vec::<String>::decode(&mut &data[..]);
+    let _ = Listing::decode(&mut &data[..]);
+});
@@ -0,0 +1,229 @@
+//! Golden-file tests pinning the ABI frontends depend on: selectors and
+//! signatures of every message, the layout of public types and events, and
+//! the SCALE encoding of sample values. A change shows up as a diff against
+//! `golden/`; if it's intended, regenerate the files with
+//! `UPDATE_GOLDEN=1 cargo test -p asset_hub_nft_final golden::` and review
+//! them with the change.
+//!
+//! They live outside the contract module because `#[ink::contract]` rejects
+//! the `__ink_` name the metadata is generated under.
+
+use crate::asset_hub_nft::{Currency, Error, Listing};
+use crate::pallet_nfts::NftsError;
+use crate::test_utils::Scenario;
+use ink::env::{test, DefaultEnvironment};
+use ink::metadata::InkProject;
+use scale::Encode;
+use scale_info::{form::PortableForm, PortableRegistry, TypeDef};
+use std::fmt::Write;
+
+extern "Rust" {
+    // Generated by `#[ink::contract]` for `cargo contract` to read the metadata
+    fn __ink_generate_metadata() -> InkProject;
+}
+
+fn metadata() -> InkProject {
+    // SAFETY: the contract macro defines it in this crate with this signature
+    unsafe { __ink_generate_metadata() }
+}
+
+/// Compare `actual` with `golden/{name}`, or overwrite it with `UPDATE_GOLDEN` set
+fn assert_golden(name: &str, actual: &str) {
+    let path = format!("{}/golden/{}", env!("CARGO_MANIFEST_DIR"), name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("can't read {}: {}; run with UPDATE_GOLDEN=1", path, err));
+    if expected == actual {
+        return;
+    }
+    let (line, expected_line, actual_line) = expected
+        .lines()
+        .chain(std::iter::repeat("<end of file>"))
+        .zip(actual.lines().chain(std::iter::repeat("<end of file>")))
+        .enumerate()
+        .find(|(_, (expected, actual))| expected != actual)
+        .map(|(index, (expected, actual))| (index + 1, expected, actual))
+        .unwrap();
+    panic!(
+        "golden/{} changed at line {}:\n  expected: {}\n    actual: {}\n\
+         This breaks the ABI for existing frontends. If that's intended, \
+         rerun with UPDATE_GOLDEN=1 and commit the new file.",
+        name, line, expected_line, actual_line
+    );
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Render a registry type as it would be written in Rust, without module paths
+fn type_name(registry: &PortableRegistry, id: u32) -> String {
+    let ty = &registry.resolve(id).unwrap();
+    let params = ty
+        .type_params
+        .iter()
+        .filter_map(|param| param.ty.map(|param| type_name(registry, param.id)))
+        .collect::<Vec<_>>();
+    if let Some(name) = ty.path.segments.last() {
+        return match params.is_empty() {
+            true => name.clone(),
+            false => format!("{}<{}>", name, params.join(", ")),
+        };
+    }
+    match &ty.type_def {
+        TypeDef::Primitive(primitive) => format!("{:?}", primitive).to_lowercase(),
+        TypeDef::Sequence(sequence) => format!("Vec<{}>", type_name(registry, sequence.type_param.id)),
+        TypeDef::Array(array) => format!("[{}; {}]", type_name(registry, array.type_param.id), array.len),
+        TypeDef::Compact(compact) => format!("Compact<{}>", type_name(registry, compact.type_param.id)),
+        TypeDef::Tuple(tuple) => {
+            let fields = tuple.fields.iter().map(|field| type_name(registry, field.id)).collect::<Vec<_>>();
+            format!("({})", fields.join(", "))
+        }
+        other => format!("{:?}", other),
+    }
+}
+
+/// Render a struct's or variant's fields, named or positional
+fn fields(registry: &PortableRegistry, fields: &[scale_info::Field<PortableForm>]) -> String {
+    let rendered = fields
+        .iter()
+        .map(|field| match &field.name {
+            Some(name) => format!("{}: {}", name, type_name(registry, field.ty.id)),
+            None => type_name(registry, field.ty.id),
+        })
+        .collect::<Vec<_>>();
+    match fields.first() {
+        None => String::new(),
+        Some(field) if field.name.is_some() => format!(" {{ {} }}", rendered.join(", ")),
+        Some(_) => format!("({})", rendered.join(", ")),
+    }
+}
+
+#[test]
+fn selectors_and_signatures() {
+    let metadata = metadata();
+    let registry = metadata.registry();
+    let spec = metadata.spec();
+    let signature = |args: &[ink::metadata::MessageParamSpec<PortableForm>]| {
+        args.iter()
+            .map(|arg| format!("{}: {}", arg.label(), type_name(registry, arg.ty().ty().id)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut golden = String::new();
+    for constructor in spec.constructors() {
+        let selector = hex(constructor.selector().to_bytes());
+        writeln!(golden, "0x{} constructor {}({})", selector, constructor.label(), signature(constructor.args()))
+            .unwrap();
+    }
+    for message in spec.messages() {
+        let returns = message
+            .return_type()
+            .opt_type()
+            .map(|ty| type_name(registry, ty.ty().id))
+            .unwrap_or_else(|| String::from("()"));
+        let kind = match (message.mutates(), message.payable()) {
+            (_, true) => "payable",
+            (true, false) => "mut",
+            (false, false) => "view",
+        };
+        let selector = hex(message.selector().to_bytes());
+        writeln!(golden, "0x{} {} {}({}) -> {}", selector, kind, message.label(), signature(message.args()), returns)
+            .unwrap();
+    }
+    assert_golden("selectors.txt", &golden);
+}
+
+#[test]
+fn types_and_events() {
+    let metadata = metadata();
+    let registry = metadata.registry();
+
+    // Every type the contract and the shared traits define, by path
+    let mut types = registry
+        .types
+        .iter()
+        .filter(|ty| {
+            let crate_name = ty.ty.path.segments.first().map(String::as_str);
+            matches!(crate_name, Some("asset_hub_nft_final" | "polka_space_nft_core"))
+        })
+        .map(|ty| (ty.ty.path.segments.join("::"), &ty.ty))
+        .collect::<Vec<_>>();
+    types.sort_by(|a, b| a.0.cmp(&b.0));
+    types.dedup_by(|a, b| a.0 == b.0);
+
+    let mut golden = String::new();
+    for (path, ty) in types {
+        match &ty.type_def {
+            TypeDef::Composite(composite) => {
+                writeln!(golden, "struct {}{}", path, fields(registry, &composite.fields)).unwrap()
+            }
+            TypeDef::Variant(variant) => {
+                writeln!(golden, "enum {}", path).unwrap();
+                for variant in &variant.variants {
+                    let fields = fields(registry, &variant.fields);
+                    writeln!(golden, "  {} {}{}", variant.index, variant.name, fields).unwrap();
+                }
+            }
+            other => writeln!(golden, "{} {:?}", path, other).unwrap(),
+        }
+    }
+
+    // Events are variants of one enum, in declaration order
+    writeln!(golden, "events").unwrap();
+    for (index, event) in metadata.spec().events().iter().enumerate() {
+        let args = event
+            .args()
+            .iter()
+            .map(|arg| {
+                let topic = if arg.indexed() { "#[topic] " } else { "" };
+                format!("{}{}: {}", topic, arg.label(), type_name(registry, arg.ty().ty().id))
+            })
+            .collect::<Vec<_>>();
+        writeln!(golden, "  {} {} {{ {} }}", index, event.label(), args.join(", ")).unwrap();
+    }
+    assert_golden("types.txt", &golden);
+}
+
+#[ink::test]
+fn sample_encodings() {
+    let accounts = test::default_accounts::<DefaultEnvironment>();
+    let mut golden = String::new();
+
+    for error in [Error::NotOwner, Error::HookRejected, Error::PalletNfts(NftsError::ItemLocked)] {
+        writeln!(golden, "{:?} 0x{}", error, hex(&error.encode())).unwrap();
+    }
+
+    let mut nft = Scenario::new()
+        .mint_to(accounts.bob, 2)
+        .approve(accounts.bob, accounts.charlie, 1)
+        .transfer(accounts.bob, accounts.eve, 1)
+        .list(accounts.bob, 2, 1_000)
+        .build();
+    test::set_caller::<DefaultEnvironment>(accounts.eve);
+    test::set_value_transferred::<DefaultEnvironment>(1_000);
+    assert_eq!(nft.buy(2), Ok(()));
+
+    let token = nft.token_metadata(2).unwrap();
+    writeln!(golden, "NFTMetadata 0x{}", hex(&token.encode())).unwrap();
+    let listing = Listing {
+        seller: accounts.bob,
+        price: 1_000,
+        currency: Currency::Psp22(accounts.frank),
+        usd_priced: false,
+    };
+    writeln!(golden, "Listing 0x{}", hex(&listing.encode())).unwrap();
+
+    // Each event with its topics, as indexers see them
+    let events = metadata().spec().events().iter().map(|event| event.label().clone()).collect::<Vec<_>>();
+    for event in test::recorded_events() {
+        let label = &events[usize::from(event.data[0])];
+        let topics = event.topics.iter().map(|topic| format!("0x{}", hex(topic))).collect::<Vec<_>>();
+        writeln!(golden, "{} 0x{} [{}]", label, hex(&event.data), topics.join(", ")).unwrap();
+    }
+    assert_golden("encodings.txt", &golden);
+}
@@ -0,0 +1,122 @@
+//! Chain extension into the runtime's `pallet_nfts`, so a collection can
+//! mirror its tokens as native Asset Hub items that wallets show.
+//! The runtime must route these function IDs to `pallet_nfts` calls made on
+//! behalf of the calling contract, which must own the collection. The
+//! collection should have item transfers locked for everyone else, so
+//! ownership only changes through the contract and the two never drift.
+//! A contract's environment has a single chain extension, so the
+//! `pallet_assets` transfers behind payments in foreign assets live here too,
+//! as do the `pallet_xcm` sends behind cross-chain transfers.
+
+use ink::env::{DefaultEnvironment, Environment};
+use ink::prelude::string::String;
+use ink::primitives::AccountId;
+use scale::{Decode, Encode};
+
+/// Collection ID type of `pallet_nfts` on Asset Hub
+pub type CollectionId = u32;
+
+/// Item ID type of `pallet_nfts` on Asset Hub
+pub type ItemId = u32;
+
+/// Asset ID type of `pallet_assets` on Asset Hub
+pub type AssetId = u32;
+
+/// Item calls of `pallet_nfts`, made by the contract as collection owner,
+/// asset transfers of `pallet_assets` and NFT messages of `pallet_xcm`
+#[ink::chain_extension]
+pub trait PalletNfts {
+    type ErrorCode = NftsError;
+
+    /// Mint `item` of `collection` to `mint_to`
+    #[ink(extension = 0x0001_0001)]
+    fn mint(collection: CollectionId, item: ItemId, mint_to: AccountId);
+
+    /// Move `item` of `collection` to `dest`
+    #[ink(extension = 0x0001_0002)]
+    fn transfer(collection: CollectionId, item: ItemId, dest: AccountId);
+
+    /// Destroy `item` of `collection`
+    #[ink(extension = 0x0001_0003)]
+    fn burn(collection: CollectionId, item: ItemId);
+
+    /// Send `amount` of the contract's own `asset` to `dest`
+    #[ink(extension = 0x0002_0001)]
+    fn asset_transfer(asset: AssetId, dest: AccountId, amount: u128);
+
+    /// Send `amount` of `owner`'s `asset` to `dest`, spending the approval
+    /// `owner` gave the contract
+    #[ink(extension = 0x0002_0002)]
+    fn asset_transfer_approved(asset: AssetId, owner: AccountId, dest: AccountId, amount: u128);
+
+    /// Send an XCM message minting `token_id` with `uri` to `dest_account` on
+    /// parachain `dest_para_id`, returning the ID of the query its outcome is
+    /// reported under
+    #[ink(extension = 0x0003_0001)]
+    fn xcm_send_nft(dest_para_id: u32, dest_account: [u8; 32], token_id: u32, uri: String) -> u64;
+
+    /// Get the outcome reported for an XCM query: `None` until the
+    /// destination answers, then whether it executed the message
+    #[ink(extension = 0x0003_0002)]
+    fn xcm_response(query_id: u64) -> Option<bool>;
+}
+
+/// Status codes the runtime returns for `PalletNfts` calls
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum NftsError {
+    /// The contract doesn't own the collection, or lacks the needed role
+    NoPermission,
+    /// The collection or item doesn't exist
+    UnknownItem,
+    /// The item ID is already taken
+    AlreadyExists,
+    /// The item is locked against transfer
+    ItemLocked,
+    /// The payer's asset balance or approval is too low
+    BalanceLow,
+    /// The destination parachain can't be reached over XCM
+    Unroutable,
+    /// Any other dispatch error
+    Failed,
+    /// The runtime's output couldn't be decoded
+    DecodeFailed,
+}
+
+impl ink::env::chain_extension::FromStatusCode for NftsError {
+    fn from_status_code(status_code: u32) -> Result<(), Self> {
+        match status_code {
+            0 => Ok(()),
+            1 => Err(Self::NoPermission),
+            2 => Err(Self::UnknownItem),
+            3 => Err(Self::AlreadyExists),
+            4 => Err(Self::ItemLocked),
+            5 => Err(Self::BalanceLow),
+            6 => Err(Self::Unroutable),
+            _ => Err(Self::Failed),
+        }
+    }
+}
+
+impl From<scale::Error> for NftsError {
+    fn from(_: scale::Error) -> Self {
+        Self::DecodeFailed
+    }
+}
+
+/// The default environment, with `PalletNfts` as its chain extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum AssetHubEnvironment {}
+
+impl Environment for AssetHubEnvironment {
+    const MAX_EVENT_TOPICS: usize = <DefaultEnvironment as Environment>::MAX_EVENT_TOPICS;
+
+    type AccountId = <DefaultEnvironment as Environment>::AccountId;
+    type Balance = <DefaultEnvironment as Environment>::Balance;
+    type Hash = <DefaultEnvironment as Environment>::Hash;
+    type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+    type Timestamp = <DefaultEnvironment as Environment>::Timestamp;
+
+    type ChainExtension = PalletNfts;
+}
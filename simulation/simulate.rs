@@ -0,0 +1,284 @@
+//! Replay years of randomized collection activity against the contract,
+//! compiled natively and run in ink!'s off-chain environment.
+//!
+//! ```text
+//! cd simulation && cargo run -- [OPS] [SEED] [YEARS]
+//! ```
+//!
+//! Defaults to 50,000 operations spread over five years of 6 second blocks,
+//! seed 0. The same seed always replays the same history. Every operation is
+//! one a real holder could make, so the contract must accept it; a model of
+//! owners and listings is checked against the contract once per simulated
+//! quarter, along with the owner index invariants. Each quarter also prints
+//! a CSV row of supply and storage cells used, to show how storage grows as
+//! the collection ages.
+//!
+//! The invariant checks are debug assertions, so run without `--release`.
+
+use std::collections::HashMap;
+
+use asset_hub_nft_final::asset_hub_nft::AssetHubNFT;
+use ink::env::{test, DefaultEnvironment};
+use ink::primitives::AccountId;
+use polka_space_nft_core::{Enumerable, PSP34Core};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Blocks produced in a year at 6 seconds each
+const BLOCKS_PER_YEAR: u64 = 365 * 24 * 60 * 10;
+
+/// Accounts that hold and trade tokens
+const HOLDERS: u32 = 500;
+
+/// Largest batch a single mint call makes
+const MAX_MINT_BATCH: usize = 10;
+
+/// Highest listing price, in the native currency's smallest unit
+const MAX_PRICE: u128 = 1_000_000_000;
+
+/// URI of every simulated token
+const URI: &str = "ipfs://QmSimulated";
+
+/// Run length and seed, from the command line
+struct Config {
+    ops: u64,
+    seed: u64,
+    years: u64,
+}
+
+impl Config {
+    fn from_args() -> Self {
+        let mut args = std::env::args().skip(1).map(|arg| {
+            arg.parse::<u64>()
+                .unwrap_or_else(|_| panic!("arguments are OPS SEED YEARS, got {:?}", arg))
+        });
+        Self {
+            ops: args.next().unwrap_or(50_000),
+            seed: args.next().unwrap_or(0),
+            years: args.next().unwrap_or(5),
+        }
+    }
+}
+
+/// IDs that can be picked at random, inserted and removed in constant time
+#[derive(Default)]
+struct Pool {
+    ids: Vec<u32>,
+    positions: HashMap<u32, usize>,
+}
+
+impl Pool {
+    fn insert(&mut self, id: u32) {
+        if !self.positions.contains_key(&id) {
+            self.positions.insert(id, self.ids.len());
+            self.ids.push(id);
+        }
+    }
+
+    fn remove(&mut self, id: u32) {
+        if let Some(position) = self.positions.remove(&id) {
+            self.ids.swap_remove(position);
+            if let Some(&moved) = self.ids.get(position) {
+                self.positions.insert(moved, position);
+            }
+        }
+    }
+
+    fn pick(&self, rng: &mut StdRng) -> Option<u32> {
+        (!self.ids.is_empty()).then(|| self.ids[rng.gen_range(0..self.ids.len())])
+    }
+
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+}
+
+/// What the collection should hold, built from the operations alone
+#[derive(Default)]
+struct Model {
+    owners: HashMap<u32, AccountId>,
+    balances: HashMap<AccountId, u32>,
+    live: Pool,
+    listed: Pool,
+    prices: HashMap<u32, u128>,
+    burned: u32,
+}
+
+impl Model {
+    fn mint(&mut self, to: AccountId, token_id: u32) {
+        self.owners.insert(token_id, to);
+        *self.balances.entry(to).or_default() += 1;
+        self.live.insert(token_id);
+    }
+
+    fn transfer(&mut self, to: AccountId, token_id: u32) {
+        let from = self.owners.insert(token_id, to).expect("transferred token has no owner");
+        *self.balances.entry(from).or_default() -= 1;
+        *self.balances.entry(to).or_default() += 1;
+        self.delist(token_id);
+    }
+
+    fn burn(&mut self, token_id: u32) {
+        let owner = self.owners.remove(&token_id).expect("burned token has no owner");
+        *self.balances.entry(owner).or_default() -= 1;
+        self.live.remove(token_id);
+        self.delist(token_id);
+        self.burned += 1;
+    }
+
+    fn list(&mut self, token_id: u32, price: u128) {
+        self.listed.insert(token_id);
+        self.prices.insert(token_id, price);
+    }
+
+    fn delist(&mut self, token_id: u32) {
+        self.listed.remove(token_id);
+        self.prices.remove(&token_id);
+    }
+}
+
+/// Holder `index`, clear of the default test accounts
+fn holder(index: u32) -> AccountId {
+    let mut bytes = [0xAA; 32];
+    bytes[..4].copy_from_slice(&index.to_le_bytes());
+    AccountId::from(bytes)
+}
+
+/// A holder other than `not`
+fn other_holder(rng: &mut StdRng, holders: &[AccountId], not: AccountId) -> AccountId {
+    loop {
+        let picked = holders[rng.gen_range(0..holders.len())];
+        if picked != not {
+            return picked;
+        }
+    }
+}
+
+/// Make one random operation, which the contract must accept
+fn step(nft: &mut AssetHubNFT, model: &mut Model, rng: &mut StdRng, admin: AccountId, holders: &[AccountId]) {
+    let as_caller = |account| test::set_caller::<DefaultEnvironment>(account);
+    let roll = rng.gen_range(0..100);
+
+    // Trading needs tokens; an empty collection mints first
+    let Some(token_id) = model.live.pick(rng).filter(|_| roll >= 13) else {
+        let to = holders[rng.gen_range(0..holders.len())];
+        as_caller(admin);
+        if roll < 3 {
+            let uris = vec![String::from(URI); rng.gen_range(2..=MAX_MINT_BATCH)];
+            for token_id in nft.mint_batch(to, uris).expect("batch mint rejected") {
+                model.mint(to, token_id);
+            }
+        } else {
+            let token_id = nft.mint(to, String::from(URI)).expect("mint rejected");
+            model.mint(to, token_id);
+        }
+        return;
+    };
+    let owner = model.owners[&token_id];
+
+    match roll {
+        13..=47 => {
+            let to = other_holder(rng, holders, owner);
+            as_caller(owner);
+            nft.transfer(to, token_id).expect("transfer rejected");
+            model.transfer(to, token_id);
+        }
+        48..=57 => {
+            // An operator moves the token on the owner's behalf
+            let operator = other_holder(rng, holders, owner);
+            let to = other_holder(rng, holders, owner);
+            as_caller(owner);
+            nft.approve(Some(operator), token_id).expect("approval rejected");
+            as_caller(operator);
+            nft.transfer(to, token_id).expect("approved transfer rejected");
+            model.transfer(to, token_id);
+        }
+        58..=72 => {
+            let price = rng.gen_range(1..=MAX_PRICE);
+            as_caller(owner);
+            assert!(nft.list(token_id, price), "listing rejected");
+            model.list(token_id, price);
+        }
+        73..=77 => {
+            let Some(listed) = model.listed.pick(rng) else { return };
+            as_caller(model.owners[&listed]);
+            assert!(nft.cancel_listing(listed), "cancelling a listing rejected");
+            model.delist(listed);
+        }
+        78..=92 => {
+            let Some(listed) = model.listed.pick(rng) else { return };
+            let buyer = other_holder(rng, holders, model.owners[&listed]);
+            as_caller(buyer);
+            test::set_value_transferred::<DefaultEnvironment>(model.prices[&listed]);
+            nft.buy(listed).expect("purchase rejected");
+            test::set_value_transferred::<DefaultEnvironment>(0);
+            model.transfer(buyer, listed);
+        }
+        _ => {
+            as_caller(owner);
+            nft.burn(token_id).expect("burn rejected");
+            model.burn(token_id);
+        }
+    }
+}
+
+/// Panic if the contract disagrees with the model or its own indexes
+fn check(nft: &AssetHubNFT, model: &Model, holders: &[AccountId]) {
+    for (&token_id, &owner) in &model.owners {
+        assert_eq!(nft.owner_of(token_id), Some(owner), "owner of token {}", token_id);
+        let price = model.prices.get(&token_id).copied();
+        assert_eq!(nft.listing_price(token_id), price, "price of token {}", token_id);
+    }
+    for &account in holders {
+        let balance = model.balances.get(&account).copied().unwrap_or(0);
+        assert_eq!(nft.balance_of(account), balance, "balance of {:?}", account);
+        assert_eq!(nft.tokens_of_owner(account).len(), balance as usize, "tokens of {:?}", account);
+    }
+    nft.debug_assert_invariants(holders);
+}
+
+fn main() {
+    let config = Config::from_args();
+    let quarters = (config.years * 4).max(1);
+    let ops_per_quarter = (config.ops / quarters).max(1);
+    let blocks_per_op = (config.years * BLOCKS_PER_YEAR / config.ops.max(1)).max(1);
+
+    test::run_test::<DefaultEnvironment, _>(|_| {
+        let admin = AccountId::from([0xAD; 32]);
+        let contract = AccountId::from([0xCC; 32]);
+        test::set_callee::<DefaultEnvironment>(contract);
+        test::set_caller::<DefaultEnvironment>(admin);
+        let mut nft = AssetHubNFT::new_default();
+
+        let holders = (0..HOLDERS).map(holder).collect::<Vec<_>>();
+        let mut model = Model::default();
+        let mut rng = StdRng::seed_from_u64(config.seed);
+
+        println!("quarter,ops,minted,live,listed,holders,storage_cells,cells_per_live_token");
+        for op in 1..=config.ops {
+            for _ in 0..blocks_per_op {
+                test::advance_block::<DefaultEnvironment>();
+            }
+            step(&mut nft, &mut model, &mut rng, admin, &holders);
+
+            if op % ops_per_quarter == 0 || op == config.ops {
+                check(&nft, &model, &holders);
+                let cells = test::count_used_storage_cells::<DefaultEnvironment>(&contract)?;
+                let live = model.live.len();
+                let holding = model.balances.values().filter(|&&balance| balance > 0).count();
+                println!(
+                    "{},{},{},{},{},{},{},{:.2}",
+                    op.div_ceil(ops_per_quarter),
+                    op,
+                    live as u32 + model.burned,
+                    live,
+                    model.listed.len(),
+                    holding,
+                    cells,
+                    cells as f64 / live.max(1) as f64,
+                );
+            }
+        }
+        Ok(())
+    })
+    .expect("the off-chain environment failed");
+}
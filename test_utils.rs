@@ -0,0 +1,146 @@
+//! Fixtures shared by the unit, property and e2e tests.
+//!
+//! A [`Scenario`] lists the setup a test needs, such as
+//! `Scenario::new().mint_to(alice, 5).approve(alice, bob, 3).build()`, and
+//! builds it into a collection in the off-chain environment. The e2e tests
+//! replay the same steps against a node. Tokens are numbered from 1 in the
+//! order they're minted.
+
+use crate::asset_hub_nft::{AssetHubNFT, MAX_BATCH_SIZE};
+#[cfg(feature = "marketplace")]
+use crate::pallet_nfts::AssetHubEnvironment;
+#[cfg(feature = "marketplace")]
+use ink::env::Environment;
+use ink::env::{test, DefaultEnvironment};
+use ink::primitives::AccountId;
+
+#[cfg(feature = "marketplace")]
+type Balance = <AssetHubEnvironment as Environment>::Balance;
+
+/// URI of every token a scenario mints
+pub const SCENARIO_URI: &str = "ipfs://QmScenario";
+
+/// One piece of setup, made by the account it names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// The admin mints `count` tokens to `to`
+    Mint { to: AccountId, count: u32 },
+    /// `owner` approves `operator` for `token_id`
+    Approve { owner: AccountId, operator: AccountId, token_id: u32 },
+    /// `from` transfers `token_id` to `to`
+    Transfer { from: AccountId, to: AccountId, token_id: u32 },
+    /// `seller` lists `token_id` at `price` in the native currency
+    #[cfg(feature = "marketplace")]
+    List { seller: AccountId, token_id: u32, price: Balance },
+}
+
+/// Setup for a test collection, applied in order by [`Scenario::build`]
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    admin: AccountId,
+    steps: Vec<Step>,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scenario {
+    /// An empty collection administered by alice
+    pub fn new() -> Self {
+        Self {
+            admin: test::default_accounts::<DefaultEnvironment>().alice,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Deploy and mint as `admin` instead of alice
+    pub fn with_admin(mut self, admin: AccountId) -> Self {
+        self.admin = admin;
+        self
+    }
+
+    /// Mint `count` tokens to `to`
+    pub fn mint_to(mut self, to: AccountId, count: u32) -> Self {
+        self.steps.push(Step::Mint { to, count });
+        self
+    }
+
+    /// Have `owner` approve `operator` for `token_id`
+    pub fn approve(mut self, owner: AccountId, operator: AccountId, token_id: u32) -> Self {
+        self.steps.push(Step::Approve { owner, operator, token_id });
+        self
+    }
+
+    /// Have `from` transfer `token_id` to `to`
+    pub fn transfer(mut self, from: AccountId, to: AccountId, token_id: u32) -> Self {
+        self.steps.push(Step::Transfer { from, to, token_id });
+        self
+    }
+
+    /// Have `seller` list `token_id` at `price`
+    #[cfg(feature = "marketplace")]
+    pub fn list(mut self, seller: AccountId, token_id: u32, price: Balance) -> Self {
+        self.steps.push(Step::List { seller, token_id, price });
+        self
+    }
+
+    /// Account that deploys the collection and mints
+    pub fn admin(&self) -> AccountId {
+        self.admin
+    }
+
+    /// Steps in the order they apply
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// URIs of a `Mint` step, in calls of at most `MAX_BATCH_SIZE` tokens
+    pub fn mint_batches(count: u32) -> impl Iterator<Item = Vec<String>> {
+        let batches = count.div_ceil(MAX_BATCH_SIZE);
+        (0..batches).map(move |batch| {
+            let size = (count - batch * MAX_BATCH_SIZE).min(MAX_BATCH_SIZE);
+            vec![String::from(SCENARIO_URI); size as usize]
+        })
+    }
+
+    /// Deploy the collection at django in the off-chain environment and
+    /// apply every step, panicking on the first that fails. Leaves the admin
+    /// as the caller.
+    pub fn build(&self) -> AssetHubNFT {
+        test::set_callee::<DefaultEnvironment>(test::default_accounts::<DefaultEnvironment>().django);
+        test::set_caller::<DefaultEnvironment>(self.admin);
+        let mut nft = AssetHubNFT::new_default();
+
+        for step in &self.steps {
+            let applied = match *step {
+                Step::Mint { to, count } => {
+                    test::set_caller::<DefaultEnvironment>(self.admin);
+                    Self::mint_batches(count).try_for_each(|uris| nft.mint_batch(to, uris).map(drop))
+                }
+                Step::Approve { owner, operator, token_id } => {
+                    test::set_caller::<DefaultEnvironment>(owner);
+                    nft.approve(Some(operator), token_id)
+                }
+                Step::Transfer { from, to, token_id } => {
+                    test::set_caller::<DefaultEnvironment>(from);
+                    nft.transfer(to, token_id)
+                }
+                #[cfg(feature = "marketplace")]
+                Step::List { seller, token_id, price } => {
+                    test::set_caller::<DefaultEnvironment>(seller);
+                    assert!(nft.list(token_id, price), "scenario step {:?} was rejected", step);
+                    Ok(())
+                }
+            };
+            if let Err(err) = applied {
+                panic!("scenario step {:?} failed: {:?}", step, err);
+            }
+        }
+
+        test::set_caller::<DefaultEnvironment>(self.admin);
+        nft
+    }
+}